@@ -0,0 +1,364 @@
+use std::{
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_std::task;
+use clap::{Parser, Subcommand};
+
+use iced_todo::{
+    formats::{self, Format},
+    model::{ItemPath, Query, Sort, TodoItem, TodoList},
+    persistance::{self, LoadError, PersistError, Persistance, SaveError},
+};
+
+#[derive(Parser)]
+#[command(version, about = "Todo list app made in iced")]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// The todo file to use, instead of the one in ICED_TODO_FILE, the settings or the default one
+    #[arg(long, global = true)]
+    pub file: Option<PathBuf>,
+
+    /// The workspace whose todo file to use
+    #[arg(long, global = true, conflicts_with = "file")]
+    pub workspace: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Show every todo list
+    Lists,
+    /// Show the items of a list, or of every list
+    List {
+        list: Option<String>,
+
+        /// The order to show the items in, manual, created, updated, completed or due, their
+        /// numbers staying the same
+        #[arg(long, default_value_t)]
+        sort: Sort,
+    },
+    /// Create a new todo list
+    NewList { name: String },
+    /// Add an item to a list
+    Add {
+        list: String,
+        #[arg(required = true)]
+        name: Vec<String>,
+    },
+    /// Mark an item as completed
+    Done {
+        list: String,
+        /// The number of the item, or of the subtask like `2.1`
+        item: ItemPath,
+
+        /// Mark the item as uncompleted instead
+        #[arg(long)]
+        undo: bool,
+    },
+    /// Remove an item from a list
+    Rm {
+        list: String,
+        /// The number of the item, or of the subtask like `2.1`
+        item: ItemPath,
+    },
+    /// Find the items matching every term, in every list
+    Search {
+        #[arg(required = true)]
+        terms: Vec<String>,
+    },
+    /// Write a list, or every list, in another format
+    Export {
+        list: Option<String>,
+
+        /// The format to write in, markdown, todo.txt, ics or csv, by default the one of the output's
+        /// extension or else markdown
+        #[arg(long)]
+        format: Option<Format>,
+
+        /// The file to write to, instead of printing
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Add the lists in a file, each heading, todo.txt project, calendar category or csv list
+    /// becoming a list
+    Import {
+        input: PathBuf,
+
+        /// The format the file is in, by default the one of its extension or else markdown
+        #[arg(long)]
+        format: Option<Format>,
+
+        /// Add every item to this list instead
+        #[arg(long)]
+        into: Option<String>,
+    },
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    Persist(PersistError),
+    NoList(String),
+    NoItem(ItemPath),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Persist(error) => write!(f, "{error}"),
+            CliError::NoList(list) => write!(f, "No todo list named \"{list}\""),
+            CliError::NoItem(item) => write!(f, "No item number {item}"),
+        }
+    }
+}
+
+impl Error for CliError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            // the persist error is shown as this one, so what caused it comes next
+            CliError::Persist(error) => error.source(),
+            CliError::NoList(_) | CliError::NoItem(_) => None,
+        }
+    }
+}
+
+impl From<PersistError> for CliError {
+    fn from(error: PersistError) -> Self {
+        CliError::Persist(error)
+    }
+}
+
+pub struct Cli;
+
+impl Persistance for Cli {
+    fn config_path() -> Result<PathBuf, PersistError> {
+        persistance::save_path()
+    }
+}
+
+impl Cli {
+    pub fn run(command: Command) -> Result<(), CliError> {
+        // a missing save file just means nothing has been saved yet
+        let mut todo_lists = if Self::config_path()?.exists() {
+            Self::load_lists()?
+        } else {
+            Vec::new()
+        };
+
+        match command {
+            Command::Lists => {
+                for (index, list) in todo_lists.iter().enumerate() {
+                    println!(
+                        "{}. {} ({}/{})",
+                        index + 1,
+                        list.name,
+                        list.completed_count(),
+                        list.todo_items.len()
+                    );
+                }
+
+                return Ok(());
+            }
+            Command::List {
+                list: Some(list),
+                sort,
+            } => {
+                print_list(find_list(&todo_lists, &list)?, sort);
+
+                return Ok(());
+            }
+            Command::List { list: None, sort } => {
+                for list in &todo_lists {
+                    print_list(list, sort);
+                }
+
+                return Ok(());
+            }
+            Command::Search { terms } => {
+                let query = Query::new(&terms.join(" "));
+
+                for list in &todo_lists {
+                    for (path, item) in list.search(&query) {
+                        println!("{} {path}. {}", list.name, item.name);
+                    }
+                }
+
+                return Ok(());
+            }
+            Command::Export {
+                list,
+                format,
+                output,
+            } => {
+                let format = format
+                    .or_else(|| Format::from_path(output.as_deref()?))
+                    .unwrap_or_default();
+                let exported = match list {
+                    Some(list) => format.export(&[find_list(&todo_lists, &list)?.clone()]),
+                    None => format.export(&todo_lists),
+                };
+
+                match output {
+                    Some(path) => fs::write(&path, exported).map_err(|error| {
+                        PersistError::Save(SaveError::Write(path, Arc::new(error)))
+                    })?,
+                    None => print!("{exported}"),
+                }
+
+                return Ok(());
+            }
+            Command::Import {
+                input,
+                format,
+                into,
+            } => {
+                let format = format
+                    .or_else(|| Format::from_path(&input))
+                    .unwrap_or_default();
+                let text = fs::read_to_string(&input).map_err(|error| {
+                    PersistError::Load(LoadError::Read(input.clone(), Arc::new(error)))
+                })?;
+                // items imported before are updated where they are
+                let imported = formats::update_existing(&mut todo_lists, format.import(&text));
+
+                match into {
+                    Some(list) => find_list_mut(&mut todo_lists, &list)?
+                        .extend(imported.into_iter().flat_map(|list| list.todo_items)),
+                    None => {
+                        let imported = imported
+                            .into_iter()
+                            .map(|mut list| {
+                                if list.name.is_empty() {
+                                    list.rename(&imported_name(&input));
+                                }
+
+                                list
+                            })
+                            .collect();
+                        // lists imported before get the new items, rather than a second list
+                        let imported = formats::merge_lists(&mut todo_lists, imported);
+
+                        todo_lists.extend(imported);
+                    }
+                }
+            }
+            Command::NewList { name } => todo_lists.push(TodoList::new(&name)),
+            Command::Add { list, name } => {
+                find_list_mut(&mut todo_lists, &list)?.add(&name.join(" "));
+            }
+            Command::Done { list, item, undo } => {
+                let list = find_list_mut(&mut todo_lists, &list)?;
+                let item = find_item(list, item)?;
+
+                list.set_completed(item, !undo);
+            }
+            Command::Rm { list, item } => {
+                let list = find_list_mut(&mut todo_lists, &list)?;
+                let item = find_item(list, item)?;
+
+                list.remove(item);
+            }
+        }
+
+        let saved = task::block_on(Self::save_lists(todo_lists))?;
+
+        // the lists are saved, so a failed backup does not fail the command
+        if let Some(error) = saved.backup_error {
+            print_error(&error);
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints the error along with everything that caused it
+pub fn print_error(error: &dyn Error) {
+    eprintln!("iced-todo: {error}");
+
+    let mut source = error.source();
+    while let Some(error) = source {
+        eprintln!("  caused by: {error}");
+        source = error.source();
+    }
+}
+
+/// The name for imported items that were not under a heading
+fn imported_name(file: &Path) -> String {
+    file.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Imported".to_owned())
+}
+
+/// Finds a list by its name or by its number in `iced-todo lists`
+fn find_list_index(todo_lists: &[TodoList], list: &str) -> Result<usize, CliError> {
+    todo_lists
+        .iter()
+        .position(|todo_list| todo_list.name.eq_ignore_ascii_case(list))
+        .or_else(|| {
+            list.parse::<usize>()
+                .ok()
+                .filter(|number| (1..=todo_lists.len()).contains(number))
+                .map(|number| number - 1)
+        })
+        .ok_or_else(|| CliError::NoList(list.to_owned()))
+}
+
+fn find_list<'a>(todo_lists: &'a [TodoList], list: &str) -> Result<&'a TodoList, CliError> {
+    Ok(&todo_lists[find_list_index(todo_lists, list)?])
+}
+
+fn find_list_mut<'a>(
+    todo_lists: &'a mut [TodoList],
+    list: &str,
+) -> Result<&'a mut TodoList, CliError> {
+    let index = find_list_index(todo_lists, list)?;
+
+    Ok(&mut todo_lists[index])
+}
+
+/// Checks that the item numbered as by `iced-todo list` is there
+fn find_item(list: &TodoList, item: ItemPath) -> Result<ItemPath, CliError> {
+    list.get(item).map(|_| item).ok_or(CliError::NoItem(item))
+}
+
+fn print_list(list: &TodoList, sort: Sort) {
+    println!("{}", list.name);
+
+    for (index, item) in sort.sorted(&list.todo_items) {
+        print_item(item, &ItemPath::from(index).to_string(), 2);
+
+        for (subtask, item) in item.subtasks.iter().enumerate() {
+            print_item(item, &ItemPath::subtask(index, subtask).to_string(), 6);
+        }
+    }
+}
+
+fn print_item(item: &TodoItem, number: &str, indent: usize) {
+    let priority = item
+        .priority
+        .map(|priority| format!("({priority}) "))
+        .unwrap_or_default();
+    let due = item
+        .due_text()
+        .map(|due| format!(" (due {due})"))
+        .unwrap_or_default();
+    let progress = item
+        .progress()
+        .map(|(completed, total)| format!(" {completed}/{total}"))
+        .unwrap_or_default();
+
+    println!(
+        "{:indent$}{}. [{}] {}{}{}{}",
+        "",
+        number,
+        if item.completed { "x" } else { " " },
+        priority,
+        item.name,
+        progress,
+        due
+    );
+}
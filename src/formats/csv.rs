@@ -0,0 +1,390 @@
+//! Comma separated values, with a row for each item, to be opened in a spreadsheet
+//!
+//! Subtasks have the uid of their item as their parent, lists without any items are left out
+
+use std::fmt::Write;
+
+use chrono::{DateTime, Local, NaiveTime, SecondsFormat, Utc};
+use uuid::Uuid;
+
+use super::{add_subtasks, add_to_list, parse_uid};
+use crate::model::{TodoItem, TodoList};
+
+const COLUMNS: [Column; 10] = [
+    Column::List,
+    Column::Name,
+    Column::Completed,
+    Column::Due,
+    Column::Priority,
+    Column::Uid,
+    Column::Parent,
+    Column::Created,
+    Column::Updated,
+    Column::CompletedAt,
+];
+
+/// What a column holds, found by the name in its header
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    List,
+    Name,
+    Completed,
+    Due,
+    Priority,
+    Uid,
+    Parent,
+    Created,
+    Updated,
+    CompletedAt,
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::List => "list",
+            Column::Name => "name",
+            Column::Completed => "completed",
+            Column::Due => "due",
+            Column::Priority => "priority",
+            Column::Uid => "uid",
+            Column::Parent => "parent",
+            Column::Created => "created",
+            Column::Updated => "updated",
+            Column::CompletedAt => "completed at",
+        }
+    }
+
+    /// Takes the header along with other names spreadsheets are likely to use
+    fn from_header(header: &str) -> Option<Column> {
+        let header = header.trim().to_lowercase();
+
+        COLUMNS.into_iter().find(|column| {
+            column.header() == header
+                || match column {
+                    Column::List => ["project", "category"].contains(&header.as_str()),
+                    Column::Name => ["item", "task", "title", "summary"].contains(&header.as_str()),
+                    Column::Completed => ["done", "status"].contains(&header.as_str()),
+                    Column::Due => ["due date", "deadline"].contains(&header.as_str()),
+                    Column::Uid => header == "id",
+                    Column::Created => ["created at", "date created"].contains(&header.as_str()),
+                    Column::Updated => {
+                        ["updated at", "modified", "last modified"].contains(&header.as_str())
+                    }
+                    Column::CompletedAt => {
+                        ["date completed", "completion date"].contains(&header.as_str())
+                    }
+                    Column::Priority | Column::Parent => false,
+                }
+        })
+    }
+}
+
+pub fn export(lists: &[TodoList]) -> String {
+    let mut csv = String::new();
+
+    write_row(&mut csv, COLUMNS.map(Column::header));
+
+    for list in lists {
+        for item in &list.todo_items {
+            write_item(&mut csv, &list.name, item, None);
+
+            for subtask in &item.subtasks {
+                write_item(&mut csv, &list.name, subtask, Some(item.uid));
+            }
+        }
+    }
+
+    csv
+}
+
+fn write_item(csv: &mut String, list: &str, item: &TodoItem, parent: Option<Uuid>) {
+    write_row(
+        csv,
+        COLUMNS.map(|column| match column {
+            Column::List => list.to_owned(),
+            Column::Name => item.name.clone(),
+            Column::Completed => item.completed.to_string(),
+            Column::Due => item.due_text().unwrap_or_default(),
+            Column::Priority => item.priority.map(String::from).unwrap_or_default(),
+            Column::Uid => item.uid.to_string(),
+            Column::Parent => parent.map(|parent| parent.to_string()).unwrap_or_default(),
+            Column::Created => format_time(item.created_at),
+            Column::Updated => format_time(item.updated_at),
+            Column::CompletedAt => format_time(item.completed_at.filter(|_| item.completed)),
+        }),
+    );
+}
+
+fn write_row<T: AsRef<str>>(csv: &mut String, fields: impl IntoIterator<Item = T>) {
+    for (index, field) in fields.into_iter().enumerate() {
+        if index > 0 {
+            csv.push(',');
+        }
+
+        let _ = write!(csv, "{}", quote(field.as_ref()));
+    }
+
+    csv.push_str("\r\n");
+}
+
+/// Quotes fields which would otherwise be split or trimmed, doubling their quotes
+fn quote(field: &str) -> String {
+    let needs_quotes = field.contains([',', '"', '\n', '\r'])
+        || field.starts_with(char::is_whitespace)
+        || field.ends_with(char::is_whitespace);
+
+    if needs_quotes {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Reads every row as an item of the list in its list column, going by the names in the header.
+/// Without a header naming the items, the columns are taken to be in the order they are exported
+/// in
+pub fn import(text: &str) -> Vec<TodoList> {
+    let mut rows = parse(text).into_iter().peekable();
+
+    let header: Vec<_> = rows
+        .peek()
+        .map(|header| {
+            header
+                .iter()
+                .map(|field| Column::from_header(field))
+                .collect()
+        })
+        .unwrap_or_default();
+    let columns = if header.contains(&Some(Column::Name)) {
+        rows.next();
+
+        header
+    } else {
+        COLUMNS.map(Some).to_vec()
+    };
+
+    let mut lists: Vec<TodoList> = Vec::new();
+    let mut subtasks = Vec::new();
+
+    for row in rows {
+        let mut list = String::new();
+        let mut parent = None;
+        let mut item = TodoItem::new("");
+        // set once the row is read, as reading the other fields changes the item
+        let (mut created_at, mut updated_at, mut completed_at) = (None, None, None);
+
+        for (column, quoted) in columns.iter().zip(row) {
+            // names keep the spaces they were quoted with
+            let field = quoted.trim();
+
+            match column {
+                Some(Column::List) => list = quoted,
+                Some(Column::Name) => item.rename(&quoted),
+                Some(Column::Completed) => item.completed = parse_completed(field),
+                Some(Column::Due) => {
+                    if let Some((date, time)) = TodoItem::parse_due_text(field) {
+                        item.set_due(Some(date), time);
+                    }
+                }
+                Some(Column::Priority) => {
+                    item.priority = field
+                        .chars()
+                        .next()
+                        .filter(|_| field.chars().count() == 1)
+                        .map(|priority| priority.to_ascii_uppercase())
+                        .filter(char::is_ascii_uppercase)
+                }
+                Some(Column::Uid) if !field.is_empty() => item.uid = parse_uid(field),
+                Some(Column::Parent) if !field.is_empty() => parent = Some(parse_uid(field)),
+                Some(Column::Created) => created_at = parse_time(field),
+                Some(Column::Updated) => updated_at = parse_time(field),
+                Some(Column::CompletedAt) => completed_at = parse_time(field),
+                _ => {}
+            }
+        }
+
+        if item.name.is_empty() {
+            continue;
+        }
+
+        // times which are not in the row are unknown
+        item.created_at = created_at;
+        item.updated_at = updated_at.or(created_at);
+        item.completed_at = completed_at.filter(|_| item.completed);
+
+        match parent {
+            Some(parent) => subtasks.push((list, parent, item)),
+            None => add_to_list(&mut lists, &list, item),
+        }
+    }
+
+    add_subtasks(&mut lists, subtasks);
+
+    lists
+}
+
+/// The time in RFC 3339, in UTC
+fn format_time(time: Option<DateTime<Utc>>) -> String {
+    time.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+/// Reads RFC 3339, or a local date and time written like due dates are
+fn parse_time(field: &str) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(field) {
+        return Some(time.with_timezone(&Utc));
+    }
+
+    let (date, time) = TodoItem::parse_due_text(field)?;
+
+    date.and_time(time.unwrap_or(NaiveTime::MIN))
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Spreadsheets write booleans in all sorts of ways
+fn parse_completed(field: &str) -> bool {
+    ["true", "yes", "y", "x", "1", "done", "completed"]
+        .iter()
+        .any(|completed| field.eq_ignore_ascii_case(completed))
+}
+
+/// Splits the text into rows of fields, quoted fields keeping their commas, quotes, new lines
+/// and the spaces around them, which are trimmed from other fields
+fn parse(text: &str) -> Vec<Vec<String>> {
+    // spreadsheets start the files they save as UTF-8 with a byte order mark
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut is_quoted = false;
+    let mut was_quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if is_quoted => {
+                if chars.next_if_eq(&'"').is_some() {
+                    field.push('"');
+                } else {
+                    is_quoted = false;
+                }
+            }
+            '"' if !was_quoted && field.trim().is_empty() => {
+                field.clear();
+                is_quoted = true;
+                was_quoted = true;
+            }
+            c if is_quoted => field.push(c),
+            ',' => row.push(end_field(&mut field, &mut was_quoted)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                row.push(end_field(&mut field, &mut was_quoted));
+                rows.push(std::mem::take(&mut row));
+            }
+            // between the closing quote and the comma
+            c if was_quoted && c.is_whitespace() => {}
+            c => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() || was_quoted {
+        row.push(end_field(&mut field, &mut was_quoted));
+        rows.push(row);
+    }
+
+    // blank lines
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+
+    rows
+}
+
+/// Takes the field that was read, trimmed unless it was quoted
+fn end_field(field: &mut String, was_quoted: &mut bool) -> String {
+    let field = std::mem::take(field);
+
+    if std::mem::take(was_quoted) {
+        field
+    } else {
+        field.trim().to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::model::ItemPath;
+
+    fn names(list: &TodoList) -> Vec<&str> {
+        list.todo_items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_names_which_need_quotes() {
+        let mut list = TodoList::new(" Home, sweet home ");
+        let names_in = [
+            r#"Say "hi""#,
+            "Eggs, milk",
+            "Line\r\nbreak",
+            "New\nline",
+            "  spaced  ",
+            "plain",
+        ];
+
+        for name in names_in {
+            list.add(name);
+        }
+
+        let read = import(&export(&[list]));
+
+        assert_eq!(read[0].name, " Home, sweet home ");
+        assert_eq!(names(&read[0]), names_in);
+    }
+
+    #[test]
+    fn round_trips_subtasks_and_fields() {
+        let mut list = TodoList::new("Home");
+        list.add("Laundry");
+        list.add("Dishes");
+        list.add_subtask(0, "Whites");
+        list.add_subtask(0, "Colors");
+        list.toggle(ItemPath::subtask(0, 0));
+        list.todo_items[1].priority = Some('A');
+        list.todo_items[1].set_due(NaiveDate::from_ymd_opt(2024, 5, 3), None);
+
+        let read = import(&export(&[list.clone()]));
+        let (item, read_item) = (&list.todo_items[0], &read[0].todo_items[0]);
+
+        assert_eq!(names(&read[0]), ["Laundry", "Dishes"]);
+        assert_eq!(read_item.uid, item.uid);
+        assert_eq!(read_item.subtasks.len(), 2);
+        assert_eq!(read_item.subtasks[0].uid, item.subtasks[0].uid);
+        assert!(read_item.subtasks[0].completed);
+        assert!(!read_item.completed);
+        assert_eq!(read[0].todo_items[1].priority, Some('A'));
+        assert_eq!(read[0].todo_items[1].due_date, list.todo_items[1].due_date);
+    }
+
+    #[test]
+    fn reads_files_with_a_byte_order_mark() {
+        let read = import("\u{feff}list,task,done\r\nHome,Dishes,yes\r\n");
+
+        assert_eq!(read[0].name, "Home");
+        assert_eq!(names(&read[0]), ["Dishes"]);
+        assert!(read[0].todo_items[0].completed);
+    }
+
+    #[test]
+    fn trims_fields_which_are_not_quoted() {
+        let read = import("list , name\n Home ,  Dishes  , \n Home , \" Pans \" \n");
+
+        assert_eq!(read[0].name, "Home");
+        assert_eq!(names(&read[0]), ["Dishes", " Pans "]);
+    }
+}
@@ -0,0 +1,378 @@
+//! iCalendar, with a `VTODO` for each item, which calendar clients can show as tasks
+//!
+//! The list of an item is its first category and subtasks are related to their item, lists
+//! without any items are left out
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use uuid::Uuid;
+
+use super::{add_subtasks, add_to_list, parse_uid};
+use crate::model::{TodoItem, TodoList};
+
+const DATE_FORMAT: &str = "%Y%m%d";
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+/// How long a line can be in bytes before it is folded onto the next
+const LINE_LENGTH: usize = 75;
+
+pub fn export(lists: &[TodoList]) -> String {
+    let mut ics = String::new();
+    let stamp = Utc::now().format(DATE_TIME_FORMAT).to_string();
+
+    write_line(&mut ics, "BEGIN:VCALENDAR");
+    write_line(&mut ics, "VERSION:2.0");
+    write_line(&mut ics, "PRODID:-//iced-todo//iced-todo//EN");
+
+    for list in lists {
+        for item in &list.todo_items {
+            write_todo(&mut ics, item, &list.name, None, &stamp);
+
+            for subtask in &item.subtasks {
+                write_todo(&mut ics, subtask, &list.name, Some(item), &stamp);
+            }
+        }
+    }
+
+    write_line(&mut ics, "END:VCALENDAR");
+
+    ics
+}
+
+fn write_todo(
+    ics: &mut String,
+    item: &TodoItem,
+    list: &str,
+    parent: Option<&TodoItem>,
+    stamp: &str,
+) {
+    write_line(ics, "BEGIN:VTODO");
+    write_line(ics, &format!("UID:{}", item.uid));
+    write_line(ics, &format!("DTSTAMP:{stamp}Z"));
+    write_line(ics, &format!("SUMMARY:{}", escape(&item.name)));
+    write_line(
+        ics,
+        if item.completed {
+            "STATUS:COMPLETED"
+        } else {
+            "STATUS:NEEDS-ACTION"
+        },
+    );
+
+    if !list.trim().is_empty() {
+        write_line(ics, &format!("CATEGORIES:{}", escape(list)));
+    }
+
+    match (item.due_date, item.due_time) {
+        (Some(date), Some(time)) => write_line(
+            ics,
+            &format!("DUE:{}", date.and_time(time).format(DATE_TIME_FORMAT)),
+        ),
+        (Some(date), None) => {
+            write_line(ics, &format!("DUE;VALUE=DATE:{}", date.format(DATE_FORMAT)))
+        }
+        _ => {}
+    }
+
+    if let Some(priority) = item.priority {
+        write_line(ics, &format!("PRIORITY:{}", to_ical_priority(priority)));
+    }
+
+    if let Some(parent) = parent {
+        write_line(ics, &format!("RELATED-TO;RELTYPE=PARENT:{}", parent.uid));
+    }
+
+    let times = [
+        ("CREATED", item.created_at),
+        ("LAST-MODIFIED", item.updated_at),
+        ("COMPLETED", item.completed_at.filter(|_| item.completed)),
+    ];
+
+    for (name, time) in times {
+        if let Some(time) = time {
+            write_line(ics, &format!("{name}:{}Z", time.format(DATE_TIME_FORMAT)));
+        }
+    }
+
+    write_line(ics, "END:VTODO");
+}
+
+/// Writes the line ending in CRLF, folding it so that no line is longer than [`LINE_LENGTH`]
+fn write_line(ics: &mut String, line: &str) {
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LENGTH {
+            ics.push_str("\r\n ");
+            length = 1;
+        }
+
+        ics.push(c);
+        length += c.len_utf8();
+    }
+
+    ics.push_str("\r\n");
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.trim().chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// `A` to `I` are 1 to 9, where 1 is the highest, any lower priority being 9 as well
+fn to_ical_priority(priority: char) -> u8 {
+    (priority as u8).saturating_sub(b'A').min(8) + 1
+}
+
+fn from_ical_priority(priority: &str) -> Option<char> {
+    let priority: u8 = priority.trim().parse().ok()?;
+
+    (1..=9)
+        .contains(&priority)
+        .then(|| char::from(b'A' + priority - 1))
+}
+
+/// A `VTODO` as it is read, before its subtasks are put under their item
+struct Todo {
+    item: TodoItem,
+    list: String,
+    parent: Option<Uuid>,
+    /// Kept aside until the end, as reading the other properties changes the item
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+impl Todo {
+    fn new() -> Self {
+        Self {
+            item: TodoItem::new(""),
+            list: String::new(),
+            parent: None,
+            created_at: None,
+            updated_at: None,
+            completed_at: None,
+        }
+    }
+
+    /// The item with the times it was read with, those which were not there being unknown
+    fn finish(mut self) -> Self {
+        self.item.created_at = self.created_at;
+        self.item.updated_at = self.updated_at.or(self.created_at);
+        self.item.completed_at = self.completed_at.filter(|_| self.item.completed);
+
+        self
+    }
+}
+
+/// Reads every `VTODO` as an item of the list named after its first category, items without any
+/// going into a list without a name
+pub fn import(text: &str) -> Vec<TodoList> {
+    let mut todos: Vec<Todo> = Vec::new();
+    let mut current: Option<Todo> = None;
+    // how deep inside components of its own, like a `VALARM`, the current `VTODO` is
+    let mut depth = 0;
+
+    for line in unfold(text) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, parameters) = name.split_once(';').unwrap_or((name, ""));
+        let parameters: Vec<_> = parameters.split(';').map(str::to_ascii_uppercase).collect();
+
+        match (name.to_ascii_uppercase().as_str(), &mut current) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VTODO") => current = Some(Todo::new()),
+            ("BEGIN", Some(_)) => depth += 1,
+            ("END", Some(_)) if depth > 0 => depth -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VTODO") => todos.extend(
+                current
+                    .take()
+                    .filter(|todo| !todo.item.name.is_empty())
+                    .map(Todo::finish),
+            ),
+            // the properties of nested components are not the item's
+            (name, Some(todo)) if depth == 0 => read_property(todo, name, &parameters, value),
+            _ => {}
+        }
+    }
+
+    let mut lists = Vec::new();
+    let mut subtasks = Vec::new();
+
+    for todo in todos {
+        match todo.parent {
+            Some(parent) => subtasks.push((todo.list, parent, todo.item)),
+            None => add_to_list(&mut lists, &todo.list, todo.item),
+        }
+    }
+
+    add_subtasks(&mut lists, subtasks);
+
+    lists
+}
+
+fn read_property(todo: &mut Todo, name: &str, parameters: &[String], value: &str) {
+    let item = &mut todo.item;
+
+    match name {
+        "UID" => item.uid = parse_uid(value),
+        "SUMMARY" => item.rename(&unescape(value)),
+        "STATUS" => item.completed = value.eq_ignore_ascii_case("COMPLETED"),
+        "CATEGORIES" => todo.list = unescape(first_value(value)),
+        "DUE" => {
+            let due = if parameters.iter().any(|parameter| parameter == "VALUE=DATE") {
+                NaiveDate::parse_from_str(value, DATE_FORMAT)
+                    .ok()
+                    .map(|date| (date, None))
+            } else {
+                parse_date_time(value).map(|due| (due.date(), Some(due.time())))
+            };
+
+            if let Some((date, time)) = due {
+                item.set_due(Some(date), time);
+            }
+        }
+        "PRIORITY" => item.priority = from_ical_priority(value),
+        "CREATED" => todo.created_at = parse_utc(value),
+        "LAST-MODIFIED" => todo.updated_at = parse_utc(value),
+        "COMPLETED" => todo.completed_at = parse_utc(value),
+        // the relation is to the parent unless it says otherwise
+        "RELATED-TO"
+            if parameters.iter().all(|parameter| {
+                !parameter.starts_with("RELTYPE=") || parameter == "RELTYPE=PARENT"
+            }) =>
+        {
+            todo.parent = Some(parse_uid(value))
+        }
+        _ => {}
+    }
+}
+
+/// Reads a local date and time, times in UTC being turned into local ones
+fn parse_date_time(value: &str) -> Option<NaiveDateTime> {
+    match value.strip_suffix('Z') {
+        Some(utc) => {
+            let utc = NaiveDateTime::parse_from_str(utc, DATE_TIME_FORMAT).ok()?;
+
+            Some(Local.from_utc_datetime(&utc).naive_local())
+        }
+        // floating times, and those in a named time zone which are taken to be the local one
+        None => NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT).ok(),
+    }
+}
+
+/// Reads a time which has to be in UTC, as the times an item was created and changed are
+fn parse_utc(value: &str) -> Option<DateTime<Utc>> {
+    let utc = NaiveDateTime::parse_from_str(value.strip_suffix('Z')?, DATE_TIME_FORMAT).ok()?;
+
+    Some(utc.and_utc())
+}
+
+/// Joins the lines which were folded, a line starting with a space or a tab going on from the last
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_owned()),
+        }
+    }
+
+    lines
+}
+
+/// The first of the values separated by commas
+fn first_value(value: &str) -> &str {
+    let mut is_escaped = false;
+
+    for (index, c) in value.char_indices() {
+        match c {
+            ',' if !is_escaped => return &value[..index],
+            '\\' => is_escaped = !is_escaped,
+            _ => is_escaped = false,
+        }
+    }
+
+    value
+}
+
+/// Undoes the escaping of [`escape`], new lines becoming spaces since names are on a single line
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push(' '),
+            Some(next) => unescaped.push(next),
+            None => {}
+        }
+    }
+
+    unescaped.trim().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_components_keep_their_properties() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VTODO\r\n\
+            UID:6f1c2d3e-0000-4000-8000-000000000001\r\n\
+            SUMMARY:Call the bank\r\n\
+            BEGIN:VALARM\r\n\
+            UID:6f1c2d3e-0000-4000-8000-0000000000ff\r\n\
+            ACTION:DISPLAY\r\n\
+            DESCRIPTION:Reminder\r\n\
+            END:VALARM\r\n\
+            STATUS:COMPLETED\r\n\
+            END:VTODO\r\n\
+            END:VCALENDAR\r\n";
+
+        let lists = import(ics);
+        let item = &lists[0].todo_items[0];
+
+        assert_eq!(
+            item.uid,
+            Uuid::parse_str("6f1c2d3e-0000-4000-8000-000000000001").unwrap()
+        );
+        assert_eq!(item.name, "Call the bank");
+        assert!(item.completed);
+    }
+
+    #[test]
+    fn round_trips_uids_and_subtasks() {
+        let mut list = TodoList::new("Errands");
+        list.add("Groceries");
+        list.add_subtask(0, "Milk");
+        list.set_completed(0, true);
+
+        let imported = import(&export(std::slice::from_ref(&list)));
+        let item = &imported[0].todo_items[0];
+
+        assert_eq!(imported[0].name, "Errands");
+        assert_eq!(item.uid, list.todo_items[0].uid);
+        assert_eq!(item.subtasks[0].uid, list.todo_items[0].subtasks[0].uid);
+        assert!(item.completed && item.subtasks[0].completed);
+    }
+}
@@ -0,0 +1,338 @@
+//! GitHub flavored Markdown, with a heading for each list and its items as a task list
+//!
+//! Importing also takes plain text, where each line is an item
+
+use std::fmt::Write;
+
+use crate::model::{TodoItem, TodoList};
+
+/// Characters which could be read as Markdown, and the parentheses which would be taken for the
+/// due date
+const ESCAPED: &[char] = &[
+    '\\', '`', '*', '_', '[', ']', '(', ')', '<', '>', '#', '~', '|', '!', '&',
+];
+
+pub fn export(lists: &[TodoList]) -> String {
+    let mut markdown = String::new();
+
+    for (index, list) in lists.iter().enumerate() {
+        if index > 0 {
+            markdown.push('\n');
+        }
+
+        let _ = writeln!(markdown, "# {}", escape(&list.name));
+
+        if !list.todo_items.is_empty() {
+            markdown.push('\n');
+        }
+
+        for item in &list.todo_items {
+            write_item(&mut markdown, item, 0);
+
+            for subtask in &item.subtasks {
+                write_item(&mut markdown, subtask, 1);
+            }
+        }
+    }
+
+    markdown
+}
+
+/// Writes the item as `- [ ] name (due date)`, indented under its parent for subtasks
+fn write_item(markdown: &mut String, item: &TodoItem, depth: usize) {
+    let _ = write!(
+        markdown,
+        "{}- [{}] {}",
+        "  ".repeat(depth),
+        if item.completed { 'x' } else { ' ' },
+        escape(&item.name)
+    );
+
+    if let Some(due) = item.due_text() {
+        let _ = write!(markdown, " (due {due})");
+    }
+
+    markdown.push('\n');
+}
+
+/// Keeps the text as it is once rendered, and on a single line
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.trim().chars() {
+        match c {
+            '\n' | '\r' => escaped.push(' '),
+            c if ESCAPED.contains(&c) => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Reads every heading as a list and every line under it as an item, lines before the first
+/// heading going into a list without a name
+pub fn import(text: &str) -> Vec<TodoList> {
+    let mut lists: Vec<TodoList> = Vec::new();
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = heading(trimmed) {
+            lists.push(TodoList::new(&unescape(name)));
+
+            continue;
+        }
+
+        let Some(item) = parse_item(trimmed) else {
+            continue;
+        };
+
+        if lists.is_empty() {
+            lists.push(TodoList::new(""));
+        }
+
+        let list = lists.last_mut().expect("a list was just pushed");
+        let is_indented = line.starts_with(' ') || line.starts_with('\t');
+
+        match list.todo_items.last_mut() {
+            Some(parent) if is_indented => {
+                parent.subtasks.push(item);
+                parent.complete_from_subtasks();
+            }
+            _ => list.todo_items.push(item),
+        }
+    }
+
+    lists
+}
+
+/// The text of a heading, which is up to six `#` and a space
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+
+    ((1..=6).contains(&level) && (text.is_empty() || text.starts_with(' '))).then(|| text.trim())
+}
+
+/// Reads `- [x] name (due date)`, or a plain line as an uncompleted item
+fn parse_item(line: &str) -> Option<TodoItem> {
+    // the bullet or number of the list item, if there is one
+    let line = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+        .or_else(|| {
+            let (number, rest) = line.split_once(['.', ')'])?;
+
+            (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+                .then(|| rest.strip_prefix(' '))
+                .flatten()
+        })
+        .unwrap_or(line)
+        .trim_start();
+
+    let (completed, line) = if let Some(rest) = line.strip_prefix("[ ]") {
+        (false, rest)
+    } else if let Some(rest) = line.strip_prefix("[x]").or(line.strip_prefix("[X]")) {
+        (true, rest)
+    } else {
+        (false, line)
+    };
+
+    // the parentheses of the due date are the only ones which are not escaped
+    let (name, due) = line
+        .trim_end()
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" (due "))
+        .and_then(|(name, due)| Some((name, TodoItem::parse_due_text(due)?)))
+        .map_or((line, None), |(name, due)| (name, Some(due)));
+
+    let name = unescape(name.trim());
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut item = TodoItem::new(&name);
+    item.completed = completed;
+
+    if let Some((date, time)) = due {
+        item.set_due(Some(date), time);
+    }
+
+    Some(item)
+}
+
+/// Undoes the escaping of [`escape`], along with that of any other punctuation
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(next) if c == '\\' && next.is_ascii_punctuation() => {
+                unescaped.push(*next);
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::*;
+    use crate::model::ItemPath;
+
+    /// What Markdown keeps of the lists, leaving out uids and times
+    fn kept(lists: &[TodoList]) -> Vec<(String, Vec<String>)> {
+        fn item(item: &TodoItem, depth: usize) -> String {
+            format!(
+                "{}{} {} {:?}",
+                "  ".repeat(depth),
+                item.completed,
+                item.name,
+                item.due_text()
+            )
+        }
+
+        lists
+            .iter()
+            .map(|list| {
+                let items = list
+                    .todo_items
+                    .iter()
+                    .flat_map(|parent| {
+                        std::iter::once(item(parent, 0))
+                            .chain(parent.subtasks.iter().map(|subtask| item(subtask, 1)))
+                    })
+                    .collect();
+
+                (list.name.clone(), items)
+            })
+            .collect()
+    }
+
+    fn lists() -> Vec<TodoList> {
+        let mut home = TodoList::new("# Home [x]");
+        let names = [
+            "# not a heading",
+            "[x] not done",
+            "Dentist (due tomorrow)",
+            r"back\slash \* and \\",
+            "1. first",
+            "2) second",
+            "* starred_ `code` <b>",
+        ];
+
+        for name in names {
+            home.add(name);
+        }
+
+        home.add_subtask(0, "[ ] sub");
+        home.add_subtask(0, "(due 2024-05-03)");
+        home.toggle(ItemPath::subtask(0, 0));
+        home.toggle(2);
+        home.set_due(
+            3,
+            NaiveDate::from_ymd_opt(2024, 5, 3),
+            NaiveTime::from_hms_opt(9, 30, 0),
+        );
+        home.set_due(4, NaiveDate::from_ymd_opt(2024, 12, 31), None);
+
+        vec![home, TodoList::new("Empty"), TodoList::new("1. Work")]
+    }
+
+    #[test]
+    fn round_trips() {
+        let lists = lists();
+
+        assert_eq!(kept(&import(&export(&lists))), kept(&lists));
+    }
+
+    #[test]
+    fn exports_the_same_once_read_again() {
+        let markdown = export(&lists());
+
+        assert_eq!(export(&import(&markdown)), markdown);
+    }
+
+    fn names(list: &TodoList) -> Vec<&str> {
+        list.todo_items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn headings_of_every_level_are_lists() {
+        let read = import("# One\n## Two\n###### Six\n####### Seven\n#hashtag\n#\n");
+        let lists: Vec<_> = read.iter().map(|list| list.name.as_str()).collect();
+
+        assert_eq!(lists, ["One", "Two", "Six", ""]);
+        assert_eq!(names(&read[2]), ["####### Seven", "#hashtag"]);
+    }
+
+    #[test]
+    fn bullets_numbers_and_plain_lines_are_items() {
+        let read =
+            import("# List\n- dash\n* star\n+ plus\n1. one\n12) twelve\nplain line\n1.5 kg\n");
+
+        assert_eq!(
+            names(&read[0]),
+            [
+                "dash",
+                "star",
+                "plus",
+                "one",
+                "twelve",
+                "plain line",
+                "1.5 kg"
+            ]
+        );
+    }
+
+    #[test]
+    fn checkboxes_and_due_dates() {
+        let read = import("- [x] done\n* [X] DONE\n- [ ] open (due 2024-05-03)\n[x] bare\n");
+        let items = &read[0].todo_items;
+
+        assert_eq!(names(&read[0]), ["done", "DONE", "open", "bare"]);
+        assert!(items[0].completed && items[1].completed && items[3].completed);
+        assert!(!items[2].completed);
+        assert_eq!(items[2].due_date, NaiveDate::from_ymd_opt(2024, 5, 3));
+    }
+
+    #[test]
+    fn indented_items_are_subtasks_of_the_item_before() {
+        let read = import("# List\n  - orphan\n- item\n  - [x] sub\n\t- tabbed\n- next\n");
+        let items = &read[0].todo_items;
+
+        // there is nothing for the first one to be under
+        assert_eq!(names(&read[0]), ["orphan", "item", "next"]);
+        let subtasks: Vec<_> = items[1].subtasks.iter().map(|item| &item.name).collect();
+        assert_eq!(subtasks, ["sub", "tabbed"]);
+        assert!(!items[1].completed);
+    }
+
+    #[test]
+    fn lines_before_the_first_heading_go_into_a_list_without_a_name() {
+        let read = import("before\n\n- [ ] also before\n# List\nafter\n");
+
+        assert_eq!(read[0].name, "");
+        assert_eq!(names(&read[0]), ["before", "also before"]);
+        assert_eq!(read[1].name, "List");
+        assert_eq!(names(&read[1]), ["after"]);
+    }
+}
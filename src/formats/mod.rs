@@ -0,0 +1,278 @@
+//! Other formats the todo lists can be written in, to be used by other tools
+
+use std::{fmt, path::Path, str::FromStr};
+
+use uuid::Uuid;
+
+use crate::model::{TodoItem, TodoList};
+
+pub mod csv;
+pub mod icalendar;
+pub mod markdown;
+pub mod todotxt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Markdown,
+    TodoTxt,
+    ICalendar,
+    Csv,
+}
+
+impl Format {
+    pub const ALL: [Format; 4] = [
+        Format::Markdown,
+        Format::TodoTxt,
+        Format::ICalendar,
+        Format::Csv,
+    ];
+
+    pub fn export(self, lists: &[TodoList]) -> String {
+        match self {
+            Format::Markdown => markdown::export(lists),
+            Format::TodoTxt => todotxt::export(lists),
+            Format::ICalendar => icalendar::export(lists),
+            Format::Csv => csv::export(lists),
+        }
+    }
+
+    /// Reads the lists written in the format, those without a name coming from text that was not
+    /// under any list
+    pub fn import(self, text: &str) -> Vec<TodoList> {
+        match self {
+            Format::Markdown => markdown::import(text),
+            Format::TodoTxt => todotxt::import(text),
+            Format::ICalendar => icalendar::import(text),
+            Format::Csv => csv::import(text),
+        }
+    }
+
+    /// The format of the file going by its extension
+    pub fn from_path(path: &Path) -> Option<Format> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+
+    /// The usual extension of files in the format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Markdown => "md",
+            Format::TodoTxt => "txt",
+            Format::ICalendar => "ics",
+            Format::Csv => "csv",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Markdown => "Markdown",
+            Format::TodoTxt => "todo.txt",
+            Format::ICalendar => "iCalendar",
+            Format::Csv => "CSV",
+        })
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        Format::ALL
+            .into_iter()
+            .find(|known| {
+                known.to_string().eq_ignore_ascii_case(format)
+                    || known.extension().eq_ignore_ascii_case(format)
+            })
+            .ok_or_else(|| format!("unknown format \"{format}\""))
+    }
+}
+
+/// Updates the items which are already in the lists, found by their uid, so that importing the
+/// same file again does not add them twice. What is left of the imported lists is new
+pub fn update_existing<'a>(
+    lists: impl IntoIterator<Item = &'a mut TodoList>,
+    imported: Vec<TodoList>,
+) -> Vec<TodoList> {
+    let mut lists: Vec<_> = lists.into_iter().collect();
+
+    imported
+        .into_iter()
+        .filter_map(|mut imported| {
+            let was_empty = imported.todo_items.is_empty();
+
+            imported.todo_items.retain(|item| {
+                let existing = lists
+                    .iter_mut()
+                    .find_map(|list| Some((list.find(item.uid)?, list)));
+                let Some((path, list)) = existing else {
+                    return true;
+                };
+                let Some(existing) = list.get_mut(path) else {
+                    return true;
+                };
+
+                // the item was made when it was first seen, not when it was imported again
+                let created_at = existing.created_at.or(item.created_at);
+                *existing = item.clone();
+                existing.created_at = created_at;
+
+                // subtasks can't have subtasks, so those imported under one go under its item
+                if path.subtask.is_some() {
+                    let subtasks = std::mem::take(&mut existing.subtasks);
+                    let parent = &mut list.todo_items[path.index];
+
+                    for subtask in subtasks {
+                        if !parent.subtasks.iter().any(|other| other.uid == subtask.uid) {
+                            parent.subtasks.push(subtask);
+                        }
+                    }
+
+                    parent.complete_from_subtasks();
+                }
+
+                list.touch();
+
+                false
+            });
+
+            // lists which only had existing items have nothing left to add
+            (was_empty || !imported.todo_items.is_empty()).then_some(imported)
+        })
+        .collect()
+}
+
+/// Adds the items of the imported lists to the lists already there with the same uid or name,
+/// so that importing a list again does not add a second one. What is left are the lists which
+/// are not there yet
+pub fn merge_lists<'a>(
+    lists: impl IntoIterator<Item = &'a mut TodoList>,
+    imported: Vec<TodoList>,
+) -> Vec<TodoList> {
+    let mut lists: Vec<_> = lists.into_iter().collect();
+
+    imported
+        .into_iter()
+        .filter_map(|imported| {
+            let Some(list) = lists
+                .iter_mut()
+                .find(|list| list.uid == imported.uid || list.name == imported.name)
+            else {
+                return Some(imported);
+            };
+
+            if !imported.todo_items.is_empty() {
+                list.extend(imported.todo_items);
+            }
+
+            None
+        })
+        .collect()
+}
+
+/// Reads a uid written by another tool, those which are not uuids being turned into one which is
+/// always the same for the uid, so that importing again still finds the item
+fn parse_uid(uid: &str) -> Uuid {
+    let uid = uid.trim();
+
+    Uuid::parse_str(uid).unwrap_or_else(|_| Uuid::new_v5(&Uuid::NAMESPACE_URL, uid.as_bytes()))
+}
+
+/// Adds the item to the list with the name, adding the list if there is none
+fn add_to_list(lists: &mut Vec<TodoList>, name: &str, item: TodoItem) {
+    match lists.iter_mut().find(|list| list.name == name) {
+        Some(list) => list.todo_items.push(item),
+        None => {
+            let mut list = TodoList::new(name);
+            list.todo_items.push(item);
+            lists.push(list);
+        }
+    }
+}
+
+/// Puts the subtasks, each with the name of its list and the uid of its item, under their item
+fn add_subtasks(lists: &mut Vec<TodoList>, subtasks: Vec<(String, Uuid, TodoItem)>) {
+    for (list, parent, item) in subtasks {
+        let parent = lists.iter_mut().find_map(|list| {
+            let path = list.find(parent).filter(|path| path.subtask.is_none())?;
+
+            list.get_mut(path)
+        });
+
+        match parent {
+            Some(parent) => {
+                parent.subtasks.push(item);
+                parent.complete_from_subtasks();
+            }
+            // subtasks of items which are not there, or which are subtasks themselves
+            None => add_to_list(lists, &list, item),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ItemPath;
+
+    fn lists() -> Vec<TodoList> {
+        let mut list = TodoList::new("Home");
+        list.add("Laundry");
+        list.add_subtask(0, "Whites");
+        list.add_subtask(0, "Colors");
+        list.toggle(ItemPath::subtask(0, 0));
+
+        vec![list]
+    }
+
+    #[test]
+    fn completing_the_last_subtask_completes_its_item() {
+        let mut lists = lists();
+        let mut imported = lists.clone();
+        let colors = imported[0].todo_items[0].subtasks.remove(1);
+        imported[0].todo_items = vec![TodoItem {
+            completed: true,
+            ..colors
+        }];
+
+        let left = update_existing(&mut lists, imported);
+
+        assert!(left.is_empty());
+        assert!(lists[0].todo_items[0].subtasks[1].completed);
+        assert!(lists[0].todo_items[0].completed);
+    }
+
+    #[test]
+    fn subtasks_of_an_imported_subtask_go_under_its_item() {
+        let mut lists = lists();
+        let mut whites = lists[0].todo_items[0].subtasks[0].clone();
+        whites.add_subtask("Socks");
+        let mut imported = TodoList::new("Other");
+        imported.todo_items.push(whites);
+
+        update_existing(&mut lists, vec![imported]);
+
+        let laundry = &lists[0].todo_items[0];
+        let names: Vec<_> = laundry.subtasks.iter().map(|item| &item.name).collect();
+        assert_eq!(names, ["Whites", "Colors", "Socks"]);
+        assert!(laundry.subtasks.iter().all(|item| item.subtasks.is_empty()));
+    }
+
+    #[test]
+    fn lists_imported_again_get_the_new_items() {
+        let mut lists = lists();
+        let mut same_uid = lists[0].clone();
+        same_uid.rename("Renamed");
+        same_uid.todo_items = vec![TodoItem::new("Dishes")];
+        let mut same_name = TodoList::new("Home");
+        same_name.add("Vacuum");
+
+        let left = merge_lists(&mut lists, vec![same_uid, same_name, TodoList::new("Work")]);
+
+        let names: Vec<_> = lists[0].todo_items.iter().map(|item| &item.name).collect();
+        assert_eq!(names, ["Laundry", "Dishes", "Vacuum"]);
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].name, "Work");
+    }
+}
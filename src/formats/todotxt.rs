@@ -0,0 +1,493 @@
+//! todo.txt, with a line for each item and its list as the `+project`
+//!
+//! The format has no subtasks nor lists of its own, so subtasks are written on their own lines
+//! after their item with a `parent:` tag holding the `id:` of their item, and lists without any
+//! items are written as a line with only their project. Spaces in list names are written as `_`,
+//! and `_` as `\_`. `@context` tags and any other `key:value` are kept in the name
+//!
+//! Only the days items were created and completed on are kept, when they were last changed and
+//! when their lists were made being lost
+
+use std::{collections::HashMap, fmt::Write};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
+
+use super::{add_subtasks, add_to_list};
+use crate::model::{
+    todo_item::{DATE_FORMAT, TIME_FORMAT},
+    TodoItem, TodoList,
+};
+
+/// How the due time is written, since values can't have a `:` in them
+const DUE_TIME_FORMAT: &str = "%H%M";
+
+pub fn export(lists: &[TodoList]) -> String {
+    let mut todo_txt = String::new();
+    // items with subtasks are numbered, for their subtasks to point to
+    let mut ids = 0;
+
+    for list in lists {
+        let project = project(&list.name);
+
+        if let Some(project) = project.as_deref().filter(|_| list.todo_items.is_empty()) {
+            let _ = writeln!(todo_txt, "+{project}");
+        }
+
+        for item in &list.todo_items {
+            let id = (!item.subtasks.is_empty()).then(|| {
+                ids += 1;
+                ids
+            });
+
+            write_item(
+                &mut todo_txt,
+                item,
+                project.as_deref(),
+                id.map(|id| ("id", id)),
+            );
+
+            for subtask in &item.subtasks {
+                write_item(
+                    &mut todo_txt,
+                    subtask,
+                    project.as_deref(),
+                    id.map(|id| ("parent", id)),
+                );
+            }
+        }
+    }
+
+    todo_txt
+}
+
+/// Writes the item as `x (A) completed created name +project due:date time:hhmm`, followed by the
+/// tag relating it to its subtasks or its item
+fn write_item(
+    todo_txt: &mut String,
+    item: &TodoItem,
+    project: Option<&str>,
+    relation: Option<(&str, usize)>,
+) {
+    let mut words = Vec::new();
+
+    if item.completed {
+        words.push("x".to_owned());
+    } else if let Some(priority) = item.priority {
+        words.push(format!("({priority})"));
+    }
+
+    // a completion date is only ever written before a creation date
+    if let Some(created) = item.created_at {
+        if let Some(completed) = item.completed_at.filter(|_| item.completed) {
+            words.push(format_day(completed));
+        }
+
+        words.push(format_day(created));
+    }
+
+    words.push(item.name.split_whitespace().collect::<Vec<_>>().join(" "));
+
+    if let Some(project) = project {
+        words.push(format!("+{project}"));
+    }
+
+    if let Some(date) = item.due_date {
+        words.push(format!("due:{}", date.format(DATE_FORMAT)));
+    }
+
+    if let Some(time) = item.due_time {
+        words.push(format!("time:{}", time.format(DUE_TIME_FORMAT)));
+    }
+
+    // completed items lose their priority, which is kept as a tag instead
+    if let Some(priority) = item.priority.filter(|_| item.completed) {
+        words.push(format!("pri:{priority}"));
+    }
+
+    if let Some((key, id)) = relation {
+        words.push(format!("{key}:{id}"));
+    }
+
+    let _ = writeln!(todo_txt, "{}", words.join(" "));
+}
+
+/// The name of the list as a project, which can't have any spaces
+fn project(name: &str) -> Option<String> {
+    let project = name
+        .split_whitespace()
+        .map(|word| word.replace('\\', "\\\\").replace('_', "\\_"))
+        .collect::<Vec<_>>()
+        .join("_");
+
+    (!project.is_empty()).then_some(project)
+}
+
+/// The name of the list written as the project by [`project`]
+fn list_name(project: &str) -> String {
+    let mut name = String::new();
+    let mut chars = project.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => name.extend(chars.next()),
+            '_' => name.push(' '),
+            c => name.push(c),
+        }
+    }
+
+    name
+}
+
+/// Reads every line as an item of the list named after its last `+project`, items without any
+/// going into a list without a name
+pub fn import(text: &str) -> Vec<TodoList> {
+    let mut lists: Vec<TodoList> = Vec::new();
+    let mut ids = HashMap::new();
+    let mut subtasks = Vec::new();
+
+    for line in text.lines() {
+        let Some(line) = parse_line(line) else {
+            continue;
+        };
+        let name = line.project.map(list_name).unwrap_or_default();
+
+        let Some(item) = line.item else {
+            // the line of a list without items
+            if !lists.iter().any(|list| list.name == name) {
+                lists.push(TodoList::new(&name));
+            }
+
+            continue;
+        };
+
+        if let Some(id) = line.id {
+            ids.insert(id, item.uid);
+        }
+
+        match line.parent {
+            Some(parent) => subtasks.push((name, parent, item)),
+            None => add_to_list(&mut lists, &name, item),
+        }
+    }
+
+    // their items can be anywhere in the file
+    let subtasks = subtasks
+        .into_iter()
+        .filter_map(|(name, parent, item)| match ids.get(&parent) {
+            Some(parent) => Some((name, *parent, item)),
+            None => {
+                add_to_list(&mut lists, &name, item);
+
+                None
+            }
+        })
+        .collect();
+
+    add_subtasks(&mut lists, subtasks);
+
+    // lists are not written down, so they are only as new as the file
+    for list in &mut lists {
+        list.created_at = None;
+        list.updated_at = None;
+    }
+
+    lists
+}
+
+/// Gives the lists and items read from todo.txt, which has no uids, the uids they had before,
+/// so that reading the same file again leaves them the same. Lists are matched by name and items
+/// by name, then by where they are, subtasks having been written as items after their item
+pub fn keep_uids(previous: &[TodoList], lists: &mut [TodoList]) {
+    let mut unmatched: Vec<_> = previous.iter().collect();
+
+    for list in lists {
+        let Some(position) = unmatched
+            .iter()
+            .position(|previous| previous.name == list.name)
+        else {
+            continue;
+        };
+        let previous = unmatched.swap_remove(position);
+        list.uid = previous.uid;
+
+        // in the order they are written
+        let mut previous_items: Vec<_> = previous
+            .todo_items
+            .iter()
+            .flat_map(|item| std::iter::once(item).chain(&item.subtasks))
+            .map(|item| Some((item.uid, item.name.as_str())))
+            .collect();
+        let mut items = Vec::new();
+
+        for item in &mut list.todo_items {
+            items.push((&mut item.uid, item.name.as_str()));
+
+            for subtask in &mut item.subtasks {
+                items.push((&mut subtask.uid, subtask.name.as_str()));
+            }
+        }
+
+        let mut is_matched = vec![false; items.len()];
+
+        for ((uid, name), is_matched) in items.iter_mut().zip(&mut is_matched) {
+            let same_name = previous_items
+                .iter_mut()
+                .find(|previous| previous.is_some_and(|(_, previous)| previous == *name));
+
+            if let Some((previous, _)) = same_name.and_then(Option::take) {
+                **uid = previous;
+                *is_matched = true;
+            }
+        }
+
+        // renamed items are still where they were
+        for (index, (uid, _)) in items.iter_mut().enumerate() {
+            if is_matched[index] {
+                continue;
+            }
+
+            if let Some((previous, _)) = previous_items.get_mut(index).and_then(Option::take) {
+                **uid = previous;
+            }
+        }
+    }
+}
+
+/// What is on a line, lines with only a project being the lines of lists without items
+struct Line<'a> {
+    project: Option<&'a str>,
+    item: Option<TodoItem>,
+    /// The number of the item, for its subtasks to point to
+    id: Option<usize>,
+    /// The number of the item the subtask is under
+    parent: Option<usize>,
+}
+
+/// Reads the item on the line, along with its project
+fn parse_line(line: &str) -> Option<Line<'_>> {
+    let mut words = line.split_whitespace().peekable();
+    let mut item = TodoItem::new("");
+    let (mut id, mut parent) = (None, None);
+
+    if words.next_if_eq(&"x").is_some() {
+        item.completed = true;
+    }
+
+    if let Some(priority) = words.peek().and_then(|word| parse_priority(word)) {
+        item.priority = Some(priority);
+        words.next();
+    }
+
+    // the completion date comes first, but only when there is a creation date after it
+    let mut dates = Vec::new();
+
+    for _ in 0..2 {
+        if let Some(date) = words.peek().and_then(|word| parse_day(word)) {
+            dates.push(date);
+            words.next();
+        }
+    }
+
+    let (completed_at, created_at) = match dates[..] {
+        [completed, created] if item.completed => (Some(completed), Some(created)),
+        [.., created] => (None, Some(created)),
+        [] => (None, None),
+    };
+
+    let mut name = Vec::new();
+
+    for word in words {
+        let is_tag = match word.split_once(':') {
+            Some(("due", date)) => NaiveDate::parse_from_str(date, DATE_FORMAT)
+                .map(|date| item.due_date = Some(date))
+                .is_ok(),
+            Some(("time", time)) => NaiveTime::parse_from_str(time, DUE_TIME_FORMAT)
+                .or_else(|_| NaiveTime::parse_from_str(time, TIME_FORMAT))
+                .map(|time| item.due_time = Some(time))
+                .is_ok(),
+            Some(("pri", priority)) => parse_priority(&format!("({priority})"))
+                .map(|priority| item.priority = Some(priority))
+                .is_some(),
+            Some(("id", number)) => number.parse().map(|number| id = Some(number)).is_ok(),
+            Some(("parent", number)) => number.parse().map(|number| parent = Some(number)).is_ok(),
+            _ => false,
+        };
+
+        if !is_tag {
+            name.push(word);
+        }
+    }
+
+    // the project is written last, so any before it are part of the name
+    let project = name
+        .iter()
+        .rposition(|word| word.len() > 1 && word.starts_with('+'))
+        .map(|index| &name.remove(index)[1..]);
+
+    if name.is_empty() {
+        return project.map(|project| Line {
+            project: Some(project),
+            item: None,
+            id: None,
+            parent: None,
+        });
+    }
+
+    item.rename(&name.join(" "));
+    item.set_due(item.due_date, item.due_time);
+    item.created_at = created_at;
+    item.updated_at = completed_at.or(created_at);
+    item.completed_at = completed_at;
+
+    Some(Line {
+        project,
+        item: Some(item),
+        id,
+        parent,
+    })
+}
+
+/// The local day of the time, as `YYYY-MM-DD`
+fn format_day(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local).format(DATE_FORMAT).to_string()
+}
+
+/// Reads a day written by [`format_day`], as the start of that day
+fn parse_day(word: &str) -> Option<DateTime<Utc>> {
+    let day = NaiveDate::parse_from_str(word, DATE_FORMAT).ok()?;
+
+    day.and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// Reads a priority like `(A)`
+fn parse_priority(word: &str) -> Option<char> {
+    let mut chars = word.strip_prefix('(')?.strip_suffix(')')?.chars();
+    let priority = chars.next().filter(char::is_ascii_uppercase)?;
+
+    chars.next().is_none().then_some(priority)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::model::ItemPath;
+
+    fn uids(lists: &[TodoList]) -> Vec<Uuid> {
+        lists
+            .iter()
+            .flat_map(|list| {
+                std::iter::once(list.uid).chain(list.todo_items.iter().map(|item| item.uid))
+            })
+            .collect()
+    }
+
+    fn lists() -> Vec<TodoList> {
+        let mut list = TodoList::new("Home chores");
+        list.add("Dishes");
+        list.add("Laundry");
+        list.add("Vacuum");
+
+        vec![list]
+    }
+
+    #[test]
+    fn reading_again_keeps_the_uids() {
+        let lists = lists();
+        let mut read = import(&export(&lists));
+
+        keep_uids(&lists, &mut read);
+        assert_eq!(uids(&read), uids(&lists));
+        assert_eq!(read[0].name, "Home chores");
+    }
+
+    #[test]
+    fn moved_and_renamed_items_keep_their_uids() {
+        let lists = lists();
+        let text = export(&lists)
+            .replace("Dishes", "Dishes and pans")
+            .lines()
+            .rev()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut read = import(&text);
+
+        keep_uids(&lists, &mut read);
+        let items = &read[0].todo_items;
+
+        // matched by name, then by where they are
+        assert_eq!(items[0].uid, lists[0].todo_items[2].uid);
+        assert_eq!(items[1].uid, lists[0].todo_items[1].uid);
+        assert_ne!(items[2].uid, lists[0].todo_items[0].uid);
+    }
+
+    #[test]
+    fn renamed_items_in_place_keep_their_uids() {
+        let lists = lists();
+        let mut read = import(&export(&lists).replace("Laundry", "Fold laundry"));
+
+        keep_uids(&lists, &mut read);
+        assert_eq!(uids(&read), uids(&lists));
+    }
+
+    #[test]
+    fn subtasks_empty_lists_and_underscores_are_kept() {
+        let mut lists = lists();
+        lists[0].add_subtask(1, "Whites");
+        lists[0].add_subtask(1, "Colors");
+        lists[0].toggle(ItemPath::subtask(1, 0));
+        lists.push(TodoList::new("my_list"));
+        lists.push(TodoList::new(r"back\slash"));
+
+        let mut read = import(&export(&lists));
+        keep_uids(&lists, &mut read);
+
+        let names: Vec<_> = read.iter().map(|list| list.name.as_str()).collect();
+        assert_eq!(names, ["Home chores", "my_list", r"back\slash"]);
+        assert!(read[1].todo_items.is_empty());
+
+        let laundry = &read[0].todo_items[1];
+        let subtasks: Vec<_> = laundry
+            .subtasks
+            .iter()
+            .map(|subtask| (subtask.name.as_str(), subtask.completed))
+            .collect();
+        assert_eq!(read[0].todo_items.len(), 3);
+        assert_eq!(subtasks, [("Whites", true), ("Colors", false)]);
+        assert_eq!(
+            laundry.subtasks[1].uid,
+            lists[0].todo_items[1].subtasks[1].uid
+        );
+        assert_eq!(uids(&read), uids(&lists));
+    }
+
+    #[test]
+    fn subtasks_are_found_wherever_their_item_is() {
+        let read = import("Colors +Laundry parent:1\nWash +Laundry id:1\nDry parent:7\n");
+
+        assert_eq!(read[0].todo_items.len(), 1);
+        assert_eq!(read[0].todo_items[0].subtasks[0].name, "Colors");
+        // subtasks of items which are not there are items of their own
+        assert_eq!(read[1].name, "");
+        assert_eq!(read[1].todo_items[0].name, "Dry");
+    }
+
+    #[test]
+    fn dates_and_tags() {
+        let read = import("x 2024-05-02 2024-05-01 Call mom due:2024-05-03 pri:B +Family\n");
+        let item = &read[0].todo_items[0];
+        let day = |at: Option<DateTime<Utc>>| at.map(|at| at.with_timezone(&Local).date_naive());
+
+        assert_eq!(read[0].name, "Family");
+        assert_eq!(item.name, "Call mom");
+        assert!(item.completed);
+        assert_eq!(item.priority, Some('B'));
+        assert_eq!(item.due_date, NaiveDate::from_ymd_opt(2024, 5, 3));
+        assert_eq!(day(item.completed_at), NaiveDate::from_ymd_opt(2024, 5, 2));
+        assert_eq!(day(item.created_at), NaiveDate::from_ymd_opt(2024, 5, 1));
+    }
+}
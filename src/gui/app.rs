@@ -1,41 +1,108 @@
-use std::path::PathBuf;
+use std::{error::Error, path::PathBuf};
+
+use iced_todo::model::{Sort, TodoList};
+use iced_todo::persistance::{self, LoadError, PersistError, Persistance, Saved};
+use iced_todo::recovery;
+use iced_todo::settings::{Settings, Workspace};
 
 use crate::utils::check_dirty;
 
-use super::persistance::{self, PersistError, Persistance};
+use super::autosave::{self, Autosave, CloseMessage};
+use super::history::{self, EditTarget, History};
+use super::shortcuts::{self, Shortcut};
+use super::styling::{self, colors};
+use super::widgets::drag::{self, Drag, DragMessage};
+use super::widgets::export::{Export, ExportMessage};
 use super::widgets::filter::{filter_button, Filter};
+use super::widgets::import::{Import, ImportMessage};
 use super::widgets::lists_bar::{ListsBar, ListsBarMessage};
-use super::widgets::todo::todo_list::{TodoList, TodoListMessage};
+use super::widgets::open_file::OpenFileMessage;
+use super::widgets::recovery::{Recovery, RecoveryMessage};
+use super::widgets::search::SearchMessage;
+use super::widgets::todo::todo_item::{ItemMessage, RegularMessage};
+use super::widgets::todo::todo_list::{TodoListMessage, TodoListWidget};
+use super::widgets::watch::{self, ConflictMessage, FileStamp};
+use super::widgets::workspaces::WorkspaceMessage;
 use iced::{
     executor,
-    widget::{column, horizontal_space, row, text},
-    Application, Command, Element, Renderer, Theme,
+    theme::Text,
+    widget::{button, column, horizontal_space, pick_list, row, text, Column},
+    window, Application, Command, Element, Renderer, Subscription, Theme,
 };
+use uuid::Uuid;
 
 pub struct Todo {
-    pub todo_lists: Vec<TodoList>,
+    pub todo_lists: Vec<TodoListWidget>,
+    /// The save file the lists are from
+    pub file: Option<PathBuf>,
     is_dark: bool,
     pub is_dirty: bool,
-    pub current_list: Option<usize>,
-    status: Result<String, PersistError>,
-    filter: Filter,
+    pub current_list: Option<ListSelection>,
+    pub status: Result<String, PersistError>,
+    pub filter: Filter,
+    pub sort: Sort,
     pub lists_bar: ListsBar,
+    pub search: String,
+    history: History,
+    pub show_help: bool,
+    pub drag: Option<Drag>,
+    /// Set when the save file could not be parsed, until the user has dealt with it
+    pub recovery: Option<Recovery>,
+    show_error_details: bool,
+    /// The path being typed in to open another file
+    pub open_file: Option<String>,
+    pub workspaces: Vec<Workspace>,
+    /// The name being typed in for a new workspace
+    pub new_workspace: Option<String>,
+    /// How the save file was when it was last loaded or saved
+    pub stamp: Option<FileStamp>,
+    /// The lists as they were last loaded or saved
+    pub synced: Vec<TodoList>,
+    /// The lists in the save file, when it changed while there were unsaved changes
+    pub conflict: Option<Vec<TodoList>>,
+    pub autosave: Autosave,
+    pub export: Option<Export>,
+    pub import: Option<Import>,
+}
+
+/// What is shown in the main pane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListSelection {
+    /// Every list at once
+    All,
+    List(Uuid),
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    List(usize, TodoListMessage),
+    /// Goes to the list with the uid
+    List(Uuid, TodoListMessage),
     SetFilter(Filter),
-    Saved(Result<(), PersistError>),
+    SetSort(Sort),
+    Saved(Result<Saved, PersistError>),
     ListsBar(ListsBarMessage),
+    Search(SearchMessage),
+    Undo,
+    Redo,
+    Shortcut(Shortcut),
+    Drag(DragMessage),
+    Recovery(RecoveryMessage),
+    ToggleErrorDetails,
+    OpenFile(OpenFileMessage),
+    Export(ExportMessage),
+    Import(ImportMessage),
+    Workspace(WorkspaceMessage),
+    PollFile,
+    /// The delay after the change with the number has passed
+    SaveDue(u64),
+    CloseRequested(window::Id),
+    Close(CloseMessage),
+    Conflict(ConflictMessage),
 }
 
 impl Persistance for Todo {
     fn config_path() -> Result<PathBuf, PersistError> {
-        let mut path_buf = dirs::config_dir().ok_or(PersistError::Path)?;
-        path_buf.push("todo_save.json");
-
-        Ok(path_buf)
+        persistance::save_path()
     }
 }
 
@@ -46,21 +113,35 @@ impl Application for Todo {
     type Theme = Theme;
 
     fn new(_flags: ()) -> (Self, Command<Self::Message>) {
-        // loading is hacky
-        let (todo_lists, error) = match Self::load::<Vec<TodoList>>() {
-            Ok(item) => (item, Ok("Loaded".to_owned())),
-            Err(error) => (Vec::new(), Err(error)),
-        };
+        let (todo_lists, status, recovery) = Self::read_save_file();
+        let file = Self::config_path().ok();
 
         (
             Self {
+                stamp: file.as_deref().and_then(FileStamp::read),
+                synced: todo_lists.iter().map(|list| list.list.clone()).collect(),
                 todo_lists,
-                status: error, // maybe update later on to be a message
+                file,
+                status, // maybe update later on to be a message
                 current_list: None,
                 is_dark: true,
                 is_dirty: false,
                 filter: Filter::All,
+                sort: Sort::Manual,
                 lists_bar: ListsBar::new(),
+                search: String::new(),
+                history: History::default(),
+                show_help: false,
+                drag: None,
+                recovery,
+                show_error_details: false,
+                open_file: None,
+                workspaces: Settings::load().workspaces(),
+                new_workspace: None,
+                conflict: None,
+                autosave: Autosave::default(),
+                export: None,
+                import: None,
             },
             Command::none(),
         )
@@ -75,21 +156,63 @@ impl Application for Todo {
             }
         };
 
-        if let Some(i) = self.current_list {
-            let list = self.todo_lists.get(i).unwrap(); // sometimes gets called while the
-
-            format!(
+        match (self.current_list, self.current()) {
+            (_, Some(list)) => format!(
                 "Iced Todo{} - {}{}",
                 dirty_to_char(self.is_dirty),
-                list.name,
+                list.list.name,
                 dirty_to_char(list.is_dirty),
-            )
-        } else {
-            format!("Iced Todo{}", dirty_to_char(self.is_dirty))
+            ),
+            (Some(ListSelection::All), _) => {
+                format!("Iced Todo{} - All", dirty_to_char(self.is_dirty))
+            }
+            _ => format!("Iced Todo{}", dirty_to_char(self.is_dirty)),
         }
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
+        // nothing else can be done until the recovery or conflict dialog is dealt with
+        if (self.recovery.is_some() || self.conflict.is_some() || self.autosave.is_close_failed())
+            && !matches!(
+                message,
+                Message::Recovery(_)
+                    | Message::Conflict(_)
+                    | Message::Saved(_)
+                    | Message::CloseRequested(_)
+                    | Message::Close(_)
+                    | Message::ToggleErrorDetails
+            )
+        {
+            return Command::none();
+        }
+
+        match message {
+            Message::PollFile => return self.poll_file(),
+            Message::SaveDue(generation) => return self.save_due(generation),
+            Message::Saved(result) => return self.saved(result),
+            Message::CloseRequested(id) => return self.close(id),
+            Message::Close(close_message) => return self.update_close(close_message),
+            _ => {}
+        }
+
+        // shortcuts go through the other messages, which take care of history and saving
+        if let Message::Shortcut(shortcut) = message {
+            return self.update_shortcut(shortcut);
+        }
+
+        // undoing should not go back to what was there before another file was opened either
+        let is_recorded = !matches!(
+            message,
+            Message::Undo
+                | Message::Redo
+                | Message::OpenFile(OpenFileMessage::Submit)
+                | Message::Workspace(WorkspaceMessage::Select(_) | WorkspaceMessage::Submit)
+        );
+        let edit = EditTarget::from_message(&message);
+        // only messages that can change the lists are worth copying them for
+        let before = history::can_change_lists(&message).then(|| self.lists());
+        let was_dirty = self.is_dirty;
+
         // saving is (kinda) hacky
         let command = match message {
             Message::SetFilter(filter) => {
@@ -97,35 +220,84 @@ impl Application for Todo {
 
                 Command::none()
             }
-            Message::Saved(result) => {
-                let total_items = self.get_total_items();
-
-                self.status = match result {
-                    Ok(_) => Ok(format!(
-                        "{} thing{} todo",
-                        total_items,
-                        if total_items != 1 { "s" } else { "" }
-                    )),
-                    Err(error) => Err(error),
-                };
+            Message::SetSort(sort) => {
+                self.sort = sort;
 
                 Command::none()
             }
-            Message::List(list_index, message) => {
-                self.todo_lists.get_mut(list_index).unwrap().update(message)
+            Message::List(
+                list,
+                TodoListMessage::Item(item, ItemMessage::Regular(RegularMessage::Grab)),
+            ) => self.update_drag(DragMessage::Grab(Drag::Item(list, item))),
+            Message::List(list, TodoListMessage::Drop(item)) => {
+                self.update_drag(DragMessage::DropOnItem(list, item))
+            }
+            Message::Drag(drag_message) => self.update_drag(drag_message),
+            Message::Recovery(recovery_message) => self.update_recovery(recovery_message),
+            Message::OpenFile(open_file_message) => self.update_open_file(open_file_message),
+            Message::Export(export_message) => self.update_export(export_message),
+            Message::Import(import_message) => self.update_import(import_message),
+            Message::Workspace(workspace_message) => self.update_workspace(workspace_message),
+            Message::PollFile
+            | Message::SaveDue(_)
+            | Message::Saved(_)
+            | Message::CloseRequested(_)
+            | Message::Close(_) => unreachable!("saving, closing and polling are handled before"),
+            Message::Conflict(conflict_message) => self.update_conflict(conflict_message),
+            Message::ToggleErrorDetails => {
+                self.show_error_details = !self.show_error_details;
+
+                Command::none()
             }
+            Message::List(list, message) => match self.list_mut(list) {
+                Some(list) => list.update(message),
+                None => Command::none(),
+            },
             Message::ListsBar(lists_bar_message) => self.update_lists_bar(lists_bar_message),
+            Message::Search(search_message) => self.update_search(search_message),
+            Message::Undo => {
+                if let Some(lists) = self.history.undo(self.lists()) {
+                    self.restore(lists);
+                }
+
+                Command::none()
+            }
+            Message::Redo => {
+                if let Some(lists) = self.history.redo(self.lists()) {
+                    self.restore(lists);
+                }
+
+                Command::none()
+            }
+            Message::Shortcut(_) => unreachable!("shortcuts are handled before"),
         };
 
+        let before =
+            before.filter(|before| self.todo_lists.iter().map(|list| &list.list).ne(before));
+        let is_changed = before.is_some();
+
+        if let Some(before) = before.filter(|_| is_recorded) {
+            self.history.record(before, edit);
+        }
+
         self.is_dirty = check_dirty(&self.is_dirty, &self.todo_lists, |list| list.is_dirty);
 
-        if self.is_dirty {
-            Command::perform(Self::save(self.todo_lists.clone()), Message::Saved)
+        // every change pushes the save back, so that typing is saved once it stops
+        if self.is_dirty && (is_changed || !was_dirty) {
+            Command::batch([command, self.schedule_save()])
         } else {
             command
         }
     }
 
+    fn subscription(&self) -> Subscription<Self::Message> {
+        Subscription::batch([
+            shortcuts::subscription(),
+            watch::subscription(),
+            autosave::subscription(),
+        ])
+    }
+
     fn view(&self) -> Element<'_, Self::Message, Self::Theme, Renderer> {
         let todo_lists_bar: Element<_> = self.lists_bar();
 
@@ -133,47 +305,126 @@ impl Application for Todo {
             // try to put this in update in the future
             let persistance_status = text(match &self.status {
                 Ok(message) => message,
-                Err(error) => match error {
-                    PersistError::Save(save_error) => match save_error {
-                        persistance::SaveError::Write => "Failed to write to save file",
-                        persistance::SaveError::Compose => "Failed to compose json data",
-                    },
-                    PersistError::Load(load_error) => match load_error {
-                        persistance::LoadError::Read => "Failed to read config file",
-                        persistance::LoadError::Parse => "Failed to parse config data",
-                    },
-                    PersistError::Path => "Could not get config directory",
-                },
+                Err(error) => error.summary(),
             })
             .size(20);
 
+            let save_state = if self.autosave.is_saving() {
+                Some("Saving...")
+            } else if self.is_dirty {
+                Some("Unsaved changes")
+            } else {
+                None
+            }
+            .map(|state| text(state).style(Text::Color(colors::text::secondary())));
+
+            let details_button = self.status.is_err().then(|| {
+                button(if self.show_error_details {
+                    "Hide details"
+                } else {
+                    "Details"
+                })
+                .on_press(Message::ToggleErrorDetails)
+                .style(styling::button::Button::TextSecondary)
+            });
+
+            let details = match &self.status {
+                Err(error) if self.show_error_details => Some(error_details(error)),
+                _ => None,
+            };
+
             let filter = row![
                 filter_button("All", &self.filter, Filter::All),
                 filter_button("Uncomplete", &self.filter, Filter::Uncomplete),
                 filter_button("Completed", &self.filter, Filter::Completed),
+                filter_button("Overdue", &self.filter, Filter::Overdue),
+                filter_button("Today", &self.filter, Filter::DueToday),
+                filter_button("Upcoming", &self.filter, Filter::Upcoming),
             ]
             .spacing(10);
 
-            row![persistance_status, horizontal_space(), filter]
+            let sort = row![
+                text("Sort by").style(Text::Color(colors::text::secondary())),
+                pick_list(Sort::ALL, Some(self.sort), Message::SetSort),
+            ]
+            .align_items(iced::Alignment::Center)
+            .spacing(5);
+
+            let open = button("Open")
+                .on_press(Message::OpenFile(OpenFileMessage::Start))
+                .style(styling::button::Button::TextSecondary);
+
+            let export = button("Export")
+                .on_press(Message::Export(ExportMessage::Start))
+                .style(styling::button::Button::TextSecondary);
+
+            let import = button("Import")
+                .on_press(Message::Import(ImportMessage::Start))
+                .style(styling::button::Button::TextSecondary);
+
+            let help = button("?")
+                .on_press(Message::Shortcut(Shortcut::ToggleHelp))
+                .style(styling::button::Button::Rounded);
+
+            let status_row = row![persistance_status]
+                .push_maybe(save_state)
+                .push_maybe(details_button)
+                .push(horizontal_space())
+                .push(filter)
+                .push(sort)
+                .push(open)
+                .push(import)
+                .push(export)
+                .push(help)
                 .align_items(iced::Alignment::Center)
+                .spacing(10);
+
+            column![status_row]
+                .push_maybe(self.open_file_bar())
+                .push_maybe(self.import_bar())
+                .push_maybe(self.export_bar())
+                .push_maybe(details)
                 .spacing(10)
                 .padding(10)
         };
 
-        let main_view: Element<_> = if let Some(current_list) = self.current_list {
-            let lists = self
-                .todo_lists
-                .get(current_list)
-                .unwrap()
-                .view(&self.filter)
-                .map(move |message| Message::List(current_list, message));
+        let main_view: Element<_> = match self.current_list {
+            _ if self.autosave.is_close_failed() => self.close_dialog(),
+            _ if self.recovery.is_some() => self.recovery_dialog(),
+            _ if self.conflict.is_some() => self.conflict_dialog(),
+            _ if self
+                .import
+                .as_ref()
+                .is_some_and(|import| import.preview.is_some()) =>
+            {
+                row![todo_lists_bar]
+                    .push_maybe(self.import_preview())
+                    .into()
+            }
+            _ if self.show_help => row![todo_lists_bar, self.help()].into(),
+            _ if !self.search.trim().is_empty() => {
+                row![todo_lists_bar, self.search_results()].into()
+            }
+            Some(ListSelection::All) => row![todo_lists_bar, self.all_lists()].into(),
+            _ => match self.current() {
+                Some(list) => {
+                    let uid = list.list.uid;
+                    let list = list
+                        .view(&self.filter, self.sort, self.drag.is_some())
+                        .map(move |message| Message::List(uid, message));
 
-            row![todo_lists_bar, lists].into()
-        } else {
-            todo_lists_bar
+                    row![todo_lists_bar, list].into()
+                }
+                None => todo_lists_bar,
+            },
         };
 
-        column![main_view, status].into()
+        // dropping anywhere else than onto an item or a list lets go
+        drag::drop_target(
+            column![main_view, status],
+            Message::Drag(DragMessage::Cancel),
+            self.drag.is_some(),
+        )
     }
 
     fn theme(&self) -> Theme {
@@ -186,13 +437,157 @@ impl Application for Todo {
 }
 
 impl Todo {
-    fn get_total_items(&self) -> u64 {
+    /// Reads the lists from the save file, recovering what it can if it can't be parsed
+    pub fn read_save_file() -> (
+        Vec<TodoListWidget>,
+        Result<String, PersistError>,
+        Option<Recovery>,
+    ) {
+        // loading is hacky
+        let (todo_lists, error) = match Self::load_lists() {
+            Ok(lists) => (
+                lists.into_iter().map(TodoListWidget::from).collect(),
+                Ok("Loaded".to_owned()),
+            ),
+            Err(error) => (Vec::new(), Err(error)),
+        };
+
+        // an unreadable save file would otherwise be saved over by the next change
+        match error {
+            Err(PersistError::Load(LoadError::Parse(..))) => {
+                let recovered = Self::config_path()
+                    .map(|path| recovery::recover(&path))
+                    .unwrap_or_default();
+
+                (
+                    recovered
+                        .lists
+                        .into_iter()
+                        .map(TodoListWidget::from)
+                        .collect(),
+                    error,
+                    Some(Recovery {
+                        quarantined: Self::quarantine(),
+                        skipped: recovered.skipped,
+                        backups: Self::backups().unwrap_or_default(),
+                    }),
+                )
+            }
+            _ => (todo_lists, error, None),
+        }
+    }
+
+    /// Swaps in the lists of another file, leaving nothing of the current one behind
+    pub fn reset(
+        &mut self,
+        todo_lists: Vec<TodoListWidget>,
+        status: Result<String, PersistError>,
+        recovery: Option<Recovery>,
+    ) {
+        self.file = Self::config_path().ok();
+        self.stamp = self.file.as_deref().and_then(FileStamp::read);
+        self.synced = todo_lists.iter().map(|list| list.list.clone()).collect();
+        self.todo_lists = todo_lists;
+        self.status = status;
+        self.recovery = recovery;
+        self.conflict = None;
+        self.current_list = None;
+        self.history = History::default();
+        self.search.clear();
+        self.drag = None;
+        self.is_dirty = false;
+    }
+
+    /// Replaces the data of every list, keeping the gui state of lists that are still there
+    pub fn restore(&mut self, lists: Vec<TodoList>) {
+        let mut widgets = std::mem::take(&mut self.todo_lists);
+
+        self.todo_lists = lists
+            .into_iter()
+            .map(|list| {
+                match widgets
+                    .iter()
+                    .position(|widget| widget.list.uid == list.uid)
+                {
+                    Some(index) => {
+                        let mut widget = widgets.swap_remove(index);
+                        widget.editing_item = None;
+
+                        if widget
+                            .selected_item
+                            .is_some_and(|selected| list.find(selected).is_none())
+                        {
+                            widget.selected_item = None;
+                        }
+
+                        widget.list = list;
+
+                        widget
+                    }
+                    None => list.into(),
+                }
+            })
+            .collect();
+
+        if let Some(ListSelection::List(uid)) = self.current_list {
+            if self.list_index(uid).is_none() {
+                self.current_list = None;
+            }
+        }
+
+        self.is_dirty = true;
+    }
+
+    /// Where the list with the uid is
+    pub fn list_index(&self, uid: Uuid) -> Option<usize> {
+        self.todo_lists.iter().position(|list| list.list.uid == uid)
+    }
+
+    pub fn list_mut(&mut self, uid: Uuid) -> Option<&mut TodoListWidget> {
+        self.todo_lists.iter_mut().find(|list| list.list.uid == uid)
+    }
+
+    /// The list being shown, unless it is every list or none
+    pub fn current(&self) -> Option<&TodoListWidget> {
+        let Some(ListSelection::List(uid)) = self.current_list else {
+            return None;
+        };
+
+        self.todo_lists.iter().find(|list| list.list.uid == uid)
+    }
+
+    /// The data of every list, without any gui state
+    pub fn lists(&self) -> Vec<TodoList> {
+        self.todo_lists
+            .iter()
+            .map(|widget| widget.list.clone())
+            .collect()
+    }
+
+    pub fn get_total_items(&self) -> u64 {
         let mut i = 0;
 
         for list in &self.todo_lists {
-            i += list.todo_items.len();
+            i += list.list.todo_items.len();
         }
 
         i.try_into().unwrap()
     }
 }
+
+/// The error followed by everything that led to it
+fn error_details(error: &PersistError) -> Element<'_, Message> {
+    let mut details = vec![text(error.to_string()).into()];
+    let mut source = error.source();
+
+    while let Some(error) = source {
+        details.push(
+            text(format!("Caused by: {error}"))
+                .style(Text::Color(colors::text::secondary()))
+                .into(),
+        );
+        source = error.source();
+    }
+
+    Column::with_children(details).spacing(5).into()
+}
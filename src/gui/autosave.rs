@@ -0,0 +1,253 @@
+use std::{path::PathBuf, time::Duration};
+
+use async_std::task;
+use iced::{
+    event,
+    widget::{button, column, container, row, text},
+    window, Command, Element, Event, Length, Subscription,
+};
+use iced_todo::{
+    model::TodoList,
+    persistance::{PersistError, Persistance, Saved},
+};
+
+use super::{
+    app::{Message, Todo},
+    styling,
+    widgets::watch::FileStamp,
+};
+
+/// How long after the last change the lists are saved, so that a burst of changes is saved once
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Keeps track of the changes waiting to be saved
+#[derive(Debug, Default)]
+pub struct Autosave {
+    /// Counts the changes, so that a save can tell when another change came after it was scheduled
+    generation: u64,
+    /// The generation being written, while a save is running
+    saving: Option<u64>,
+    /// The lists being written, which are only known to be in the file once the save is done
+    writing: Vec<TodoList>,
+    /// What has to wait for the running save, as it writes to the save file too
+    after_save: Option<AfterSave>,
+    /// The window that could not be closed as the lists failed to save
+    failed_close: Option<window::Id>,
+}
+
+/// What is done once the running save is over
+#[derive(Debug, Clone)]
+enum AfterSave {
+    Close(window::Id),
+    Open(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub enum CloseMessage {
+    /// Tries to save again before closing
+    Retry,
+    /// Closes, losing the unsaved changes
+    Discard,
+    /// Stays open
+    Cancel,
+}
+
+impl Autosave {
+    pub fn is_saving(&self) -> bool {
+        self.saving.is_some()
+    }
+
+    /// Opens the file with [`Todo::open`] once the running save is over
+    pub fn open_after_save(&mut self, path: PathBuf) {
+        self.after_save = Some(AfterSave::Open(path));
+    }
+
+    pub fn is_close_failed(&self) -> bool {
+        self.failed_close.is_some()
+    }
+}
+
+/// Lets the window close only once the lists are saved
+pub fn subscription() -> Subscription<Message> {
+    event::listen_with(|event, _status| match event {
+        Event::Window(id, window::Event::CloseRequested) => Some(Message::CloseRequested(id)),
+        _ => None,
+    })
+}
+
+impl Todo {
+    /// Saves once nothing has changed for a while
+    pub fn schedule_save(&mut self) -> Command<Message> {
+        self.autosave.generation += 1;
+        let generation = self.autosave.generation;
+
+        Command::perform(task::sleep(SAVE_DELAY), move |()| {
+            Message::SaveDue(generation)
+        })
+    }
+
+    pub fn save_due(&mut self, generation: u64) -> Command<Message> {
+        // a later change has its own save coming, a running save is followed up once it is done,
+        // and nothing is saved over a file changed by something else before the user picks
+        if generation != self.autosave.generation
+            || self.autosave.is_saving()
+            || !self.is_dirty
+            || self.conflict.is_some()
+        {
+            return Command::none();
+        }
+
+        self.autosave.saving = Some(generation);
+        self.autosave.writing = self.lists();
+
+        Command::perform(
+            Self::save_lists(self.autosave.writing.clone()),
+            Message::Saved,
+        )
+    }
+
+    pub fn saved(&mut self, result: Result<Saved, PersistError>) -> Command<Message> {
+        let command = self.update_saved(result);
+
+        match self.autosave.after_save.take() {
+            Some(AfterSave::Close(id)) => Command::batch([command, self.close(id)]),
+            Some(AfterSave::Open(path)) => {
+                self.open(path);
+
+                command
+            }
+            None => command,
+        }
+    }
+
+    fn update_saved(&mut self, result: Result<Saved, PersistError>) -> Command<Message> {
+        let saved = self.autosave.saving.take();
+        let written = std::mem::take(&mut self.autosave.writing);
+
+        match result {
+            Ok(done) => {
+                self.synced = written;
+
+                let total_items = self.get_total_items();
+
+                self.stamp = self.file.as_deref().and_then(FileStamp::read);
+                // the lists are saved all the same, so the failed backup is only shown
+                self.status = match done.backup_error {
+                    Some(error) => Err(error),
+                    None => Ok(format!(
+                        "{} thing{} todo",
+                        total_items,
+                        if total_items != 1 { "s" } else { "" }
+                    )),
+                };
+
+                if saved == Some(self.autosave.generation) {
+                    self.is_dirty = false;
+
+                    for list in &mut self.todo_lists {
+                        list.is_dirty = false;
+                    }
+
+                    Command::none()
+                } else {
+                    // what changed while saving still has to be saved
+                    self.schedule_save()
+                }
+            }
+            // left unsaved, to be tried again on the next change
+            Err(error) => {
+                self.status = Err(error);
+
+                Command::none()
+            }
+        }
+    }
+
+    /// Saves whatever is left before closing, as the delayed save would never come
+    pub fn close(&mut self, id: window::Id) -> Command<Message> {
+        // two saves at once would write over each other
+        if self.autosave.is_saving() {
+            self.autosave.after_save = Some(AfterSave::Close(id));
+
+            return Command::none();
+        }
+
+        // the changes can't be saved while the file they would go over has changed, so the user
+        // picks whether to lose them or to deal with the conflict first
+        if self.is_dirty && self.conflict.is_some() {
+            self.autosave.failed_close = Some(id);
+
+            return Command::none();
+        }
+
+        if self.is_dirty {
+            if let Err(error) = task::block_on(Self::save_lists(self.lists())) {
+                // the user picks whether to lose the changes
+                self.status = Err(error);
+                self.autosave.failed_close = Some(id);
+
+                return Command::none();
+            }
+        }
+
+        window::close(id)
+    }
+
+    pub fn update_close(&mut self, close_message: CloseMessage) -> Command<Message> {
+        let Some(id) = self.autosave.failed_close.take() else {
+            return Command::none();
+        };
+
+        match close_message {
+            CloseMessage::Retry => self.close(id),
+            CloseMessage::Discard => window::close(id),
+            CloseMessage::Cancel => Command::none(),
+        }
+    }
+
+    /// Shown in place of everything else when the lists could not be saved before closing
+    pub fn close_dialog(&self) -> Element<'_, Message> {
+        let title = text("Could not save").size(50);
+        let explanation = text(match &self.status {
+            _ if self.conflict.is_some() => {
+                "The save file changed while there were unsaved changes, which are lost by \
+                 closing now."
+                    .to_owned()
+            }
+            Err(error) => format!("The changes could not be saved before closing: {error}"),
+            Ok(_) => "The changes could not be saved before closing.".to_owned(),
+        });
+
+        // trying again would only run into the conflict again
+        let retry = self.conflict.is_none().then(|| {
+            button("Try again")
+                .on_press(Message::Close(CloseMessage::Retry))
+                .style(styling::button::Button::Rounded)
+        });
+        let discard = button("Close without saving")
+            .on_press(Message::Close(CloseMessage::Discard))
+            .style(styling::button::Button::DangerText);
+        let cancel = button("Cancel")
+            .on_press(Message::Close(CloseMessage::Cancel))
+            .style(styling::button::Button::TextSecondary);
+
+        container(
+            column![
+                title,
+                explanation,
+                row![]
+                    .push_maybe(retry)
+                    .push(discard)
+                    .push(cancel)
+                    .spacing(15)
+            ]
+            .padding(15)
+            .spacing(15)
+            .max_width(600)
+            .align_items(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .center_x()
+        .into()
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::VecDeque;
+
+use iced_todo::model::TodoList;
+use uuid::Uuid;
+
+use super::{
+    app::Message,
+    widgets::{
+        drag::DragMessage,
+        import::ImportMessage,
+        lists_bar::{self, ListsBarMessage, NewListMessage},
+        open_file::OpenFileMessage,
+        todo::{
+            todo_item::{EditMessage, ItemMessage},
+            todo_list::TodoListMessage,
+        },
+        workspaces::WorkspaceMessage,
+    },
+};
+
+/// How many changes can be undone
+const HISTORY_DEPTH: usize = 100;
+
+/// Snapshots of every list taken before each change
+#[derive(Default)]
+pub struct History {
+    undo: VecDeque<Vec<TodoList>>,
+    redo: Vec<Vec<TodoList>>,
+    last_edit: Option<EditTarget>,
+}
+
+/// Text being typed into, so that a whole edit is undone at once instead of a key at a time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditTarget {
+    ListName(Uuid),
+    Item(Uuid, Uuid),
+}
+
+impl EditTarget {
+    pub fn from_message(message: &Message) -> Option<Self> {
+        match message {
+            Message::List(
+                list,
+                TodoListMessage::Item(
+                    item,
+                    ItemMessage::Edit(
+                        EditMessage::Name(_) | EditMessage::DueDate(_) | EditMessage::DueTime(_),
+                    ),
+                ),
+            ) => Some(Self::Item(*list, *item)),
+            Message::ListsBar(ListsBarMessage::Edit(list, lists_bar::EditMessage::Name(_))) => {
+                Some(Self::ListName(*list))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether the message can change the lists, so that the ones from before need to be kept
+pub fn can_change_lists(message: &Message) -> bool {
+    !matches!(
+        message,
+        Message::SetFilter(_)
+            | Message::SetSort(_)
+            | Message::Search(_)
+            | Message::ToggleErrorDetails
+            | Message::Export(_)
+            | Message::Drag(DragMessage::Grab(_) | DragMessage::Cancel)
+            | Message::List(_, TodoListMessage::InputEdit(_))
+            | Message::ListsBar(
+                ListsBarMessage::Select(_)
+                    | ListsBarMessage::SelectAll
+                    | ListsBarMessage::AddingList
+                    | ListsBarMessage::Grab(_)
+                    | ListsBarMessage::NewList(NewListMessage::Input(_))
+            )
+            | Message::OpenFile(
+                OpenFileMessage::Start | OpenFileMessage::Input(_) | OpenFileMessage::Cancel
+            )
+            | Message::Import(
+                ImportMessage::Start
+                    | ImportMessage::Format(_)
+                    | ImportMessage::Path(_)
+                    | ImportMessage::Append(_)
+                    | ImportMessage::Cancel
+            )
+            | Message::Workspace(WorkspaceMessage::Adding | WorkspaceMessage::Input(_))
+    )
+}
+
+impl History {
+    /// Records the lists from before a change, unless it continues the previous edit
+    pub fn record(&mut self, before: Vec<TodoList>, edit: Option<EditTarget>) {
+        if edit.is_none() || edit != self.last_edit {
+            if self.undo.len() == HISTORY_DEPTH {
+                self.undo.pop_front();
+            }
+
+            self.undo.push_back(before);
+        }
+
+        self.redo.clear();
+        self.last_edit = edit;
+    }
+
+    /// Swaps the current lists for the ones from before the last change
+    pub fn undo(&mut self, current: Vec<TodoList>) -> Option<Vec<TodoList>> {
+        let previous = self.undo.pop_back()?;
+
+        self.redo.push(current);
+        self.last_edit = None;
+
+        Some(previous)
+    }
+
+    /// Swaps the current lists for the ones from before the last undo
+    pub fn redo(&mut self, current: Vec<TodoList>) -> Option<Vec<TodoList>> {
+        let next = self.redo.pop()?;
+
+        self.undo.push_back(current);
+        self.last_edit = None;
+
+        Some(next)
+    }
+}
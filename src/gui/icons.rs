@@ -21,7 +21,7 @@ impl IconType {
             .unwrap_or_else(|| panic!("IconType: {:?} does not have coresponding character", &self))
     }
 
-    pub fn get_text(&self) -> Text {
+    pub fn get_text(&self) -> Text<'_> {
         text(self.get_char()).font(Font::with_name("todo-icons"))
     }
 }
@@ -1,5 +1,7 @@
 pub mod app;
+mod autosave;
+mod history;
 mod icons;
-mod persistance;
+mod shortcuts;
 mod styling;
-mod widgets;
+pub mod widgets;
@@ -0,0 +1,336 @@
+use chrono::Local;
+use iced::{
+    event,
+    keyboard::{self, key::Named, Key},
+    theme::Text,
+    widget::{column, horizontal_space, row, text, text_input, Column},
+    Application, Command, Element, Event, Subscription,
+};
+use iced_todo::model::Sort;
+use uuid::Uuid;
+
+use super::{
+    app::{ListSelection, Message, Todo},
+    styling::colors,
+    widgets::{
+        lists_bar::{self, ListsBarMessage},
+        todo::{
+            todo_item::{self, EditMessage, ItemMessage, RegularMessage},
+            todo_list::{self, TodoListMessage},
+        },
+    },
+};
+
+#[derive(Debug, Clone)]
+pub enum Shortcut {
+    SelectPrevious,
+    SelectNext,
+    /// Moves the selected item down if true, or up
+    MoveSelected(bool),
+    /// Moves the current list down if true, or up
+    MoveList(bool),
+    ToggleSelected,
+    EditSelected,
+    DeleteSelected,
+    NewItem,
+    NewList,
+    SwitchList(usize),
+    Cancel,
+    ToggleHelp,
+}
+
+/// The keys shown in the help, in the order they are shown
+const BINDINGS: &[(&str, &str)] = &[
+    ("Up / Down", "Select an item"),
+    ("Alt+Up / Alt+Down", "Move the selected item"),
+    ("Alt+Shift+Up / Alt+Shift+Down", "Move the current list"),
+    ("Drag ≡", "Move an item or list, or an item to another list"),
+    ("Space", "Complete the selected item"),
+    ("E / Enter", "Edit the selected item"),
+    ("Delete", "Delete the selected item"),
+    ("Ctrl+N", "New item"),
+    ("Ctrl+Shift+N", "New list"),
+    ("Ctrl+1..9", "Switch list"),
+    ("Ctrl+Z", "Undo"),
+    ("Ctrl+Shift+Z", "Redo"),
+    ("Escape", "Stop editing"),
+    ("? / F1", "Show this help"),
+];
+
+pub fn subscription() -> Subscription<Message> {
+    event::listen_with(to_message)
+}
+
+fn to_message(event: Event, status: event::Status) -> Option<Message> {
+    let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event else {
+        return None;
+    };
+
+    // text inputs take escape for themselves, but it should still stop editing
+    if key == Key::Named(Named::Escape) {
+        return Some(Message::Shortcut(Shortcut::Cancel));
+    }
+
+    // otherwise keys typed into a text input are left alone
+    if status == event::Status::Captured {
+        return None;
+    }
+
+    let shortcut = match key.as_ref() {
+        Key::Character(c) if modifiers.command() => match c.to_lowercase().as_str() {
+            "z" if modifiers.shift() => return Some(Message::Redo),
+            "z" => return Some(Message::Undo),
+            "n" if modifiers.shift() => Shortcut::NewList,
+            "n" => Shortcut::NewItem,
+            digit => Shortcut::SwitchList(digit.parse::<usize>().ok()?.checked_sub(1)?),
+        },
+        Key::Named(Named::ArrowUp) if modifiers.alt() && modifiers.shift() => {
+            Shortcut::MoveList(false)
+        }
+        Key::Named(Named::ArrowDown) if modifiers.alt() && modifiers.shift() => {
+            Shortcut::MoveList(true)
+        }
+        Key::Named(Named::ArrowUp) if modifiers.alt() => Shortcut::MoveSelected(false),
+        Key::Named(Named::ArrowDown) if modifiers.alt() => Shortcut::MoveSelected(true),
+        Key::Named(Named::ArrowUp) => Shortcut::SelectPrevious,
+        Key::Named(Named::ArrowDown) => Shortcut::SelectNext,
+        Key::Named(Named::Space) => Shortcut::ToggleSelected,
+        Key::Character("e") | Key::Named(Named::Enter) => Shortcut::EditSelected,
+        Key::Named(Named::Delete) => Shortcut::DeleteSelected,
+        Key::Character("?") | Key::Named(Named::F1) => Shortcut::ToggleHelp,
+        _ => return None,
+    };
+
+    Some(Message::Shortcut(shortcut))
+}
+
+impl Todo {
+    pub fn update_shortcut(&mut self, shortcut: Shortcut) -> Command<Message> {
+        match shortcut {
+            Shortcut::SelectPrevious => self.move_selection(false),
+            Shortcut::SelectNext => self.move_selection(true),
+            Shortcut::MoveSelected(forward) => match self.selected() {
+                // moving only changes the order the items were put in
+                Some((list, item)) if self.sort == Sort::Manual => {
+                    let shown = self
+                        .current()
+                        .map(|list| {
+                            list.shown_items(&self.filter, self.sort, Local::now().naive_local())
+                        })
+                        .unwrap_or_default();
+
+                    // swap places with the next shown item, skipping over the hidden ones
+                    let target =
+                        shown
+                            .iter()
+                            .position(|shown| *shown == item)
+                            .and_then(|position| {
+                                if forward {
+                                    shown.get(position + 1)
+                                } else {
+                                    shown.get(position.checked_sub(1)?)
+                                }
+                            });
+
+                    match target.copied() {
+                        Some(target) => {
+                            let command = self
+                                .update(Message::List(list, TodoListMessage::Move(item, target)));
+
+                            Command::batch([
+                                command,
+                                self.current()
+                                    .map(|list| list.scroll_to(&self.filter, self.sort, item))
+                                    .unwrap_or_else(Command::none),
+                            ])
+                        }
+                        None => Command::none(),
+                    }
+                }
+                _ => Command::none(),
+            },
+            Shortcut::MoveList(forward) => match self.current_list {
+                Some(ListSelection::List(uid)) => {
+                    let target = self.list_index(uid).and_then(|index| {
+                        let target = if forward {
+                            index + 1
+                        } else {
+                            index.checked_sub(1)?
+                        };
+
+                        self.todo_lists.get(target).map(|target| target.list.uid)
+                    });
+
+                    match target {
+                        Some(target) => {
+                            self.update(Message::ListsBar(ListsBarMessage::Move(uid, target)))
+                        }
+                        None => Command::none(),
+                    }
+                }
+                _ => Command::none(),
+            },
+            Shortcut::ToggleSelected => match self.selected() {
+                Some((list, item)) => {
+                    let completed = self
+                        .current()
+                        .and_then(|list| list.list.get(list.list.find(item)?))
+                        .is_some_and(|item| item.completed);
+
+                    self.update(Message::List(
+                        list,
+                        TodoListMessage::Item(
+                            item,
+                            ItemMessage::Regular(RegularMessage::Completed(!completed)),
+                        ),
+                    ))
+                }
+                None => Command::none(),
+            },
+            Shortcut::EditSelected => match self.selected() {
+                Some((list, item)) => Command::batch([
+                    self.update(Message::List(
+                        list,
+                        TodoListMessage::Item(
+                            item,
+                            ItemMessage::Regular(RegularMessage::StartEdit),
+                        ),
+                    )),
+                    text_input::focus(todo_item::name_input_id()),
+                ]),
+                None => Command::none(),
+            },
+            Shortcut::DeleteSelected => match self.selected() {
+                Some((list, item)) => {
+                    let shown = self
+                        .current()
+                        .map(|list| {
+                            list.shown_items(&self.filter, self.sort, Local::now().naive_local())
+                        })
+                        .unwrap_or_default();
+                    let position = shown.iter().position(|shown| *shown == item);
+
+                    let command = self.update(Message::List(
+                        list,
+                        TodoListMessage::Item(item, ItemMessage::Edit(EditMessage::Delete)),
+                    ));
+
+                    // keep selecting whatever took the place of the deleted item
+                    let next = position.and_then(|position| {
+                        shown.get(position + 1).or(position
+                            .checked_sub(1)
+                            .and_then(|previous| shown.get(previous)))
+                    });
+
+                    if let Some(list) = self.list_mut(list) {
+                        list.selected_item = next.copied();
+                    }
+
+                    command
+                }
+                None => Command::none(),
+            },
+            Shortcut::NewItem => match self.current_list {
+                Some(ListSelection::List(_)) => text_input::focus(todo_list::new_item_id()),
+                _ => Command::none(),
+            },
+            Shortcut::NewList => {
+                self.lists_bar.is_adding_list = true;
+
+                text_input::focus(lists_bar::new_list_id())
+            }
+            Shortcut::SwitchList(index) => {
+                if let Some(list) = self.todo_lists.get(index) {
+                    self.current_list = Some(ListSelection::List(list.list.uid));
+                    self.search.clear();
+                }
+
+                Command::none()
+            }
+            Shortcut::Cancel => {
+                for list in &mut self.todo_lists {
+                    list.editing_item = None;
+                    list.is_editing = false;
+                }
+
+                self.lists_bar.is_adding_list = false;
+                self.new_workspace = None;
+                self.open_file = None;
+                self.export = None;
+                self.import = None;
+                self.show_help = false;
+
+                Command::none()
+            }
+            Shortcut::ToggleHelp => {
+                self.show_help = !self.show_help;
+
+                Command::none()
+            }
+        }
+    }
+
+    /// The uids of the current list and of its selected item
+    fn selected(&self) -> Option<(Uuid, Uuid)> {
+        let list = self.current()?;
+        let item = list
+            .selected_item
+            .filter(|item| list.list.find(*item).is_some())?;
+
+        Some((list.list.uid, item))
+    }
+
+    fn move_selection(&mut self, forward: bool) -> Command<Message> {
+        let filter = self.filter.clone();
+        let sort = self.sort;
+        let Some(ListSelection::List(uid)) = self.current_list else {
+            return Command::none();
+        };
+        let Some(list) = self.list_mut(uid) else {
+            return Command::none();
+        };
+
+        let shown = list.shown_items(&filter, sort, Local::now().naive_local());
+        // a selected subtask moves on from its item
+        let selected = list
+            .selected_item
+            .and_then(|selected| list.list.find(selected))
+            .and_then(|path| list.list.get(path.index));
+        let position =
+            selected.and_then(|selected| shown.iter().position(|shown| *shown == selected.uid));
+
+        let next = match position {
+            Some(position) if forward => shown.get(position + 1).or(shown.last()),
+            Some(position) => shown.get(position.saturating_sub(1)),
+            None if forward => shown.first(),
+            None => shown.last(),
+        };
+
+        list.selected_item = next.copied();
+
+        match list.selected_item {
+            Some(item) => list.scroll_to(&filter, sort, item),
+            None => Command::none(),
+        }
+    }
+
+    pub fn help(&self) -> Element<'_, Message> {
+        let bindings = BINDINGS.iter().map(|(keys, action)| {
+            row![
+                text(keys),
+                horizontal_space(),
+                text(action).style(Text::Color(colors::text::secondary())),
+            ]
+            .into()
+        });
+
+        column![
+            text("Shortcuts").size(50),
+            Column::with_children(bindings).spacing(10).max_width(500),
+        ]
+        .padding(15)
+        .spacing(15)
+        .align_items(iced::Alignment::Center)
+        .into()
+    }
+}
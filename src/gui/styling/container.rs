@@ -14,6 +14,8 @@ pub enum Container {
     ListsBar,
 
     CurrentItem,
+
+    Tooltip,
 }
 
 impl From<Container> for iced::theme::Container {
@@ -47,6 +49,15 @@ impl container::StyleSheet for Container {
                 },
                 ..Default::default()
             },
+            Container::Tooltip => container::Appearance {
+                background: Some(Background::Color(with_background::background())),
+                border: Border {
+                    color: colors::container::with_background::border(),
+                    width: 1.0,
+                    radius: Radius::from(ROUNDING),
+                },
+                ..Default::default()
+            },
         }
     }
 }
@@ -0,0 +1,52 @@
+use chrono::Local;
+use iced::{
+    theme::Text,
+    widget::{column, scrollable, text, Column},
+    Element,
+};
+
+use crate::gui::{
+    app::{Message, Todo},
+    styling::colors,
+};
+
+use super::todo::todo_list::empty_view;
+
+impl Todo {
+    /// The items of every list that pass the filter, grouped under the name of their list
+    pub fn all_lists(&self) -> Element<'_, Message> {
+        let title = text("All").size(50);
+        let now = Local::now().naive_local();
+
+        let groups = self
+            .todo_lists
+            .iter()
+            .filter_map(|list| {
+                let uid = list.list.uid;
+                let items = list.view_items(&self.filter, self.sort, now, self.drag.is_some());
+
+                (!items.is_empty()).then(|| {
+                    let name = text(&list.list.name)
+                        .size(30)
+                        .style(Text::Color(colors::text::secondary()));
+                    let items = Element::from(Column::with_children(items).spacing(10))
+                        .map(move |message| Message::List(uid, message));
+
+                    column![name, items].spacing(10).into()
+                })
+            })
+            .collect::<Vec<Element<_>>>();
+
+        let todo_items: Element<_> = if groups.is_empty() {
+            empty_view(&self.filter)
+        } else {
+            scrollable(Column::with_children(groups).spacing(25).padding(10)).into()
+        };
+
+        column![title, todo_items]
+            .padding(15)
+            .spacing(15)
+            .align_items(iced::Alignment::Center)
+            .into()
+    }
+}
@@ -0,0 +1,140 @@
+use iced::{
+    theme::Text,
+    widget::{mouse_area, text},
+    Command, Element,
+};
+use iced_todo::model::Sort;
+use uuid::Uuid;
+
+use crate::gui::{
+    app::{Message, Todo},
+    styling::colors,
+};
+
+/// What is being dragged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Drag {
+    /// The item with the second uid, in the list with the first
+    Item(Uuid, Uuid),
+    List(Uuid),
+}
+
+#[derive(Debug, Clone)]
+pub enum DragMessage {
+    Grab(Drag),
+    /// Dropped onto the item with the second uid, in the list with the first
+    DropOnItem(Uuid, Uuid),
+    /// Dropped onto the list in the lists bar
+    DropOnList(Uuid),
+    Cancel,
+}
+
+/// The handle to press on to start dragging
+pub fn grip<'a, M: Clone + 'a>(on_grab: M) -> Element<'a, M> {
+    mouse_area(text("≡").style(Text::Color(colors::text::secondary())))
+        .on_press(on_grab)
+        .into()
+}
+
+/// Sends `on_drop` when something is dropped onto the content
+pub fn drop_target<'a, M: Clone + 'a>(
+    content: impl Into<Element<'a, M>>,
+    on_drop: M,
+    is_dragging: bool,
+) -> Element<'a, M> {
+    if is_dragging {
+        mouse_area(content).on_release(on_drop).into()
+    } else {
+        content.into()
+    }
+}
+
+impl Todo {
+    pub fn update_drag(&mut self, drag_message: DragMessage) -> Command<Message> {
+        // moving within a list only changes the order the items were put in, which is not what
+        // is shown when they are sorted
+        let is_manual = self.sort == Sort::Manual;
+        let can_move = |from_list, to_list| is_manual || from_list != to_list;
+
+        match (drag_message, self.drag.take()) {
+            (DragMessage::Grab(drag), _) => self.drag = Some(drag),
+            (DragMessage::DropOnItem(to_list, to), Some(Drag::Item(from_list, from)))
+                if can_move(from_list, to_list) =>
+            {
+                self.move_item(from_list, from, to_list, Some(to));
+            }
+            (DragMessage::DropOnList(to_list), Some(Drag::Item(from_list, from)))
+                if can_move(from_list, to_list) =>
+            {
+                self.move_item(from_list, from, to_list, None);
+            }
+            (DragMessage::DropOnList(to), Some(Drag::List(from))) => self.move_list(from, to),
+            _ => {}
+        }
+
+        Command::none()
+    }
+
+    /// Moves an item before the item `to`, or to the end of the list if there is no `to`
+    pub fn move_item(&mut self, from_list: Uuid, from: Uuid, to_list: Uuid, to: Option<Uuid>) {
+        let (Some(from_list), Some(to_list)) =
+            (self.list_index(from_list), self.list_index(to_list))
+        else {
+            return;
+        };
+        // only whole items are dragged, and only onto whole items
+        let Some(from) = self.todo_lists[from_list]
+            .list
+            .find(from)
+            .filter(|path| path.subtask.is_none())
+            .map(|path| path.index)
+        else {
+            return;
+        };
+        let to = match to.map(|to| self.todo_lists[to_list].list.find(to)) {
+            Some(Some(path)) if path.subtask.is_none() => Some(path.index),
+            Some(_) => return,
+            None => None,
+        };
+
+        if from_list == to_list {
+            let list = &mut self.todo_lists[from_list];
+            let to = to.unwrap_or(list.list.todo_items.len().saturating_sub(1));
+
+            if list.list.move_item(from, to).is_some() {
+                list.editing_item = None;
+                list.selected_item = Some(list.list.todo_items[to].uid);
+                list.is_dirty = true;
+            }
+
+            return;
+        }
+
+        let Some(item) = self.todo_lists[from_list].list.remove(from) else {
+            return;
+        };
+
+        let list = &mut self.todo_lists[to_list];
+        let to = to.unwrap_or(list.list.todo_items.len());
+        list.list.insert(to, item);
+
+        for index in [from_list, to_list] {
+            let list = &mut self.todo_lists[index];
+            list.editing_item = None;
+            list.selected_item = None;
+            list.is_dirty = true;
+        }
+    }
+
+    /// Moves a list to the position of the list `to`
+    pub fn move_list(&mut self, from: Uuid, to: Uuid) {
+        let (Some(from), Some(to)) = (self.list_index(from), self.list_index(to)) else {
+            return;
+        };
+
+        let list = self.todo_lists.remove(from);
+        self.todo_lists.insert(to, list);
+
+        self.is_dirty = true;
+    }
+}
@@ -0,0 +1,143 @@
+use std::{fs, path::Path, sync::Arc};
+
+use iced::{
+    clipboard,
+    widget::{button, checkbox, pick_list, row, text_input},
+    Command, Element, Length,
+};
+use iced_todo::{
+    formats::Format,
+    model::TodoList,
+    persistance::{PersistError, SaveError},
+};
+
+use crate::gui::{
+    app::{Message, Todo},
+    styling,
+};
+
+/// What to export and where
+#[derive(Debug, Clone, Default)]
+pub struct Export {
+    pub format: Format,
+    /// Every list instead of the current one
+    pub all: bool,
+    pub path: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExportMessage {
+    Start,
+    Format(Format),
+    All(bool),
+    Path(String),
+    Save,
+    Copy,
+    Cancel,
+}
+
+impl Todo {
+    pub fn export_bar(&self) -> Option<Element<'_, Message>> {
+        let export = self.export.as_ref()?;
+
+        let format = pick_list(Format::ALL, Some(export.format), |format| {
+            Message::Export(ExportMessage::Format(format))
+        });
+        let all = checkbox("All lists", export.all || !self.has_current_list())
+            .on_toggle(|all| Message::Export(ExportMessage::All(all)))
+            .style(styling::checkbox::Checkbox);
+        let path = text_input("Path to export to", &export.path)
+            .on_input(|input| Message::Export(ExportMessage::Path(input)))
+            .on_submit(Message::Export(ExportMessage::Save))
+            .width(Length::Fill)
+            .style(styling::text_input::TextInput);
+        let save = button("Save")
+            .on_press(Message::Export(ExportMessage::Save))
+            .style(styling::button::Button::Rounded);
+        let copy = button("Copy")
+            .on_press(Message::Export(ExportMessage::Copy))
+            .style(styling::button::Button::Rounded);
+        let cancel = button("Cancel")
+            .on_press(Message::Export(ExportMessage::Cancel))
+            .style(styling::button::Button::TextSecondary);
+
+        Some(
+            row![format, all, path, save, copy, cancel]
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .into(),
+        )
+    }
+
+    pub fn update_export(&mut self, export_message: ExportMessage) -> Command<Message> {
+        if let ExportMessage::Start = export_message {
+            self.export = Some(Export::default());
+
+            return Command::none();
+        }
+
+        let Some(export) = &mut self.export else {
+            return Command::none();
+        };
+
+        match export_message {
+            ExportMessage::Start => {}
+            ExportMessage::Format(format) => export.format = format,
+            ExportMessage::All(all) => export.all = all,
+            ExportMessage::Path(path) => {
+                if let Some(format) = Format::from_path(Path::new(path.trim())) {
+                    export.format = format;
+                }
+
+                export.path = path;
+            }
+            ExportMessage::Save => {
+                let path = export.path.trim().to_owned();
+
+                if path.is_empty() {
+                    return Command::none();
+                }
+
+                let exported = self.exported();
+
+                self.status = match fs::write(&path, exported) {
+                    Ok(()) => Ok(format!("Exported to {path}")),
+                    Err(error) => Err(PersistError::Save(SaveError::Write(
+                        path.into(),
+                        Arc::new(error),
+                    ))),
+                };
+                self.export = None;
+            }
+            ExportMessage::Copy => {
+                let exported = self.exported();
+
+                self.status = Ok("Copied to the clipboard".to_owned());
+                self.export = None;
+
+                return clipboard::write(exported);
+            }
+            ExportMessage::Cancel => self.export = None,
+        }
+
+        Command::none()
+    }
+
+    fn has_current_list(&self) -> bool {
+        self.current().is_some()
+    }
+
+    /// The lists picked to export, written in the format picked
+    fn exported(&self) -> String {
+        let Some(export) = &self.export else {
+            return String::new();
+        };
+
+        let lists: Vec<TodoList> = match self.current() {
+            Some(list) if !export.all => vec![list.list.clone()],
+            _ => self.lists(),
+        };
+
+        export.format.export(&lists)
+    }
+}
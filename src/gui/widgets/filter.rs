@@ -1,28 +1,47 @@
+use chrono::NaiveDateTime;
 use iced::widget::{button, container, Button};
 
-use crate::gui::{app::Message, styling};
+use iced_todo::model::TodoItem;
 
-use super::todo::todo_item::TodoItem;
+use crate::gui::{app::Message, styling};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum Filter {
+    #[default]
     All,
     Uncomplete,
     Completed,
+    Overdue,
+    DueToday,
+    Upcoming,
 }
 
-impl Default for Filter {
-    fn default() -> Self {
-        Self::All
-    }
-}
+/// How many days ahead [`Filter::Upcoming`] looks
+const UPCOMING_DAYS: u64 = 7;
 
 impl Filter {
-    pub fn filter(&self, todoitem: &TodoItem) -> bool {
+    pub fn filter(&self, todoitem: &TodoItem, now: NaiveDateTime) -> bool {
         match *self {
             Filter::All => true,
             Filter::Uncomplete => !todoitem.completed,
             Filter::Completed => todoitem.completed,
+            Filter::Overdue => todoitem.is_overdue(now),
+            Filter::DueToday => !todoitem.completed && todoitem.is_due_on(now.date()),
+            Filter::Upcoming => {
+                !todoitem.completed && todoitem.is_due_within(now.date(), UPCOMING_DAYS)
+            }
+        }
+    }
+
+    /// What to show when no items pass the filter
+    pub fn empty_message(&self) -> &'static str {
+        match *self {
+            Filter::All => "Add a new item todo",
+            Filter::Uncomplete => "Nothing Todo!",
+            Filter::Completed => "Nothing Completed...",
+            Filter::Overdue => "Nothing Overdue!",
+            Filter::DueToday => "Nothing due today",
+            Filter::Upcoming => "Nothing due this week",
         }
     }
 }
@@ -0,0 +1,242 @@
+use std::{fs, path::Path, sync::Arc};
+
+use iced::{
+    clipboard,
+    theme::Text,
+    widget::{button, checkbox, column, pick_list, row, scrollable, text, text_input, Column},
+    Command, Element, Length,
+};
+use iced_todo::{
+    formats::{self, Format},
+    model::TodoList,
+    persistance::{LoadError, PersistError},
+};
+
+use crate::gui::{
+    app::{Message, Todo},
+    styling::{self, colors},
+};
+
+use super::todo::todo_list::TodoListWidget;
+
+/// Lists read from a file or the clipboard, waiting to be looked over before being added
+#[derive(Debug, Clone, Default)]
+pub struct Import {
+    pub format: Format,
+    pub path: String,
+    /// What was read, shown before anything is added
+    pub preview: Option<Vec<TodoList>>,
+    /// Adds every item to the current list instead of adding the lists
+    pub append: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum ImportMessage {
+    Start,
+    Format(Format),
+    Path(String),
+    Load,
+    Paste,
+    Pasted(Option<String>),
+    Append(bool),
+    Confirm,
+    Cancel,
+}
+
+/// The name for imported items that were not under a heading
+const UNNAMED_LIST: &str = "Imported";
+
+impl Todo {
+    pub fn import_bar(&self) -> Option<Element<'_, Message>> {
+        let import = self.import.as_ref()?;
+
+        let format = pick_list(Format::ALL, Some(import.format), |format| {
+            Message::Import(ImportMessage::Format(format))
+        });
+        let path = text_input("Path to import from", &import.path)
+            .on_input(|input| Message::Import(ImportMessage::Path(input)))
+            .on_submit(Message::Import(ImportMessage::Load))
+            .width(Length::Fill)
+            .style(styling::text_input::TextInput);
+        let load = button("Load")
+            .on_press(Message::Import(ImportMessage::Load))
+            .style(styling::button::Button::Rounded);
+        let paste = button("Paste")
+            .on_press(Message::Import(ImportMessage::Paste))
+            .style(styling::button::Button::Rounded);
+        let cancel = button("Cancel")
+            .on_press(Message::Import(ImportMessage::Cancel))
+            .style(styling::button::Button::TextSecondary);
+
+        Some(
+            row![format, path, load, paste, cancel]
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .into(),
+        )
+    }
+
+    /// What would be imported, shown in place of the lists until it is confirmed
+    pub fn import_preview(&self) -> Option<Element<'_, Message>> {
+        let import = self.import.as_ref()?;
+        let preview = import.preview.as_ref()?;
+
+        let title = text("Import").size(50);
+
+        let lists = preview.iter().map(|list| {
+            let items = list.todo_items.iter().flat_map(|item| {
+                let subtasks = item.subtasks.iter().map(|subtask| (subtask, 1));
+
+                std::iter::once((item, 0)).chain(subtasks)
+            });
+
+            column![text(if list.name.is_empty() {
+                UNNAMED_LIST
+            } else {
+                &list.name
+            })
+            .size(25)]
+            .extend(items.map(|(item, depth)| {
+                text(format!(
+                    "{}[{}] {}{}",
+                    "    ".repeat(depth),
+                    if item.completed { "x" } else { " " },
+                    item.name,
+                    item.due_text()
+                        .map(|due| format!(" (due {due})"))
+                        .unwrap_or_default()
+                ))
+                .style(Text::Color(colors::text::secondary()))
+                .into()
+            }))
+            .spacing(5)
+            .into()
+        });
+
+        let has_current_list = self.current().is_some();
+        let append = checkbox("Add the items to the current list", import.append)
+            .on_toggle_maybe(
+                has_current_list.then_some(|append| Message::Import(ImportMessage::Append(append))),
+            )
+            .style(styling::checkbox::Checkbox);
+
+        let confirm = button("Import")
+            .on_press(Message::Import(ImportMessage::Confirm))
+            .style(styling::button::Button::Rounded);
+        let cancel = button("Cancel")
+            .on_press(Message::Import(ImportMessage::Cancel))
+            .style(styling::button::Button::TextSecondary);
+
+        let content: Element<_> = if preview.is_empty() {
+            text("Nothing to import")
+                .size(30)
+                .style(Text::Color(colors::text::secondary()))
+                .into()
+        } else {
+            scrollable(Column::with_children(lists).spacing(15).padding(10))
+                .width(Length::Fill)
+                .into()
+        };
+
+        Some(
+            column![title, content, append, row![confirm, cancel].spacing(15)]
+                .padding(15)
+                .spacing(15)
+                .align_items(iced::Alignment::Center)
+                .into(),
+        )
+    }
+
+    pub fn update_import(&mut self, import_message: ImportMessage) -> Command<Message> {
+        if let ImportMessage::Start = import_message {
+            self.import = Some(Import::default());
+
+            return Command::none();
+        }
+
+        let Some(import) = &mut self.import else {
+            return Command::none();
+        };
+
+        match import_message {
+            ImportMessage::Start => {}
+            ImportMessage::Format(format) => import.format = format,
+            ImportMessage::Path(path) => {
+                if let Some(format) = Format::from_path(Path::new(path.trim())) {
+                    import.format = format;
+                }
+
+                import.path = path;
+            }
+            ImportMessage::Load => {
+                let path = Path::new(import.path.trim());
+
+                match fs::read_to_string(path) {
+                    Ok(text) => import.preview = Some(import.format.import(&text)),
+                    Err(error) => {
+                        self.status = Err(PersistError::Load(LoadError::Read(
+                            path.into(),
+                            Arc::new(error),
+                        )))
+                    }
+                }
+            }
+            ImportMessage::Paste => {
+                return clipboard::read(|text| Message::Import(ImportMessage::Pasted(text)))
+            }
+            ImportMessage::Pasted(text) => {
+                import.preview = Some(import.format.import(&text.unwrap_or_default()));
+            }
+            ImportMessage::Append(append) => import.append = append,
+            ImportMessage::Confirm => {
+                if let Some(import) = self.import.take() {
+                    self.add_imported(import);
+                }
+            }
+            ImportMessage::Cancel => self.import = None,
+        }
+
+        Command::none()
+    }
+
+    fn add_imported(&mut self, import: Import) {
+        // items imported before are updated where they are
+        let lists = formats::update_existing(
+            self.todo_lists.iter_mut().map(|list| &mut list.list),
+            import.preview.unwrap_or_default(),
+        );
+        self.is_dirty = true;
+
+        let current = self.current().map(|list| list.list.uid);
+
+        match current.and_then(|uid| self.list_mut(uid)) {
+            Some(list) if import.append => {
+                list.list
+                    .extend(lists.into_iter().flat_map(|list| list.todo_items));
+                list.is_dirty = true;
+            }
+            _ => {
+                let lists = lists
+                    .into_iter()
+                    .map(|mut list| {
+                        if list.name.is_empty() {
+                            list.rename(UNNAMED_LIST);
+                        }
+
+                        list
+                    })
+                    .collect();
+                // lists imported before get the new items, rather than a second list
+                let lists = formats::merge_lists(
+                    self.todo_lists.iter_mut().map(|list| &mut list.list),
+                    lists,
+                );
+
+                self.todo_lists
+                    .extend(lists.into_iter().map(TodoListWidget::from));
+            }
+        }
+
+        self.status = Ok("Imported".to_owned());
+    }
+}
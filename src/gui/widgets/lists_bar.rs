@@ -5,30 +5,41 @@ use iced::{
     },
     Alignment, Command, Element, Length,
 };
+use uuid::Uuid;
 
 use crate::{
     gui::{
-        app::{Message, Todo},
+        app::{ListSelection, Message, Todo},
         icons::IconType,
         styling,
     },
     SIDEBAR_WIDTH,
 };
 
-use super::todo::todo_list::TodoList;
+use super::{
+    drag::{self, Drag, DragMessage},
+    todo::todo_list::TodoListWidget,
+};
 
 pub struct ListsBar {
     pub new_list_input: String,
     pub is_adding_list: bool,
 }
 
+/// Lists are found by their uid, whatever has happened to the lists since the message was sent
 #[derive(Debug, Clone)]
 pub enum ListsBarMessage {
-    Regular(usize, RegularMessage),
-    Edit(usize, EditMessage),
+    Regular(Uuid, RegularMessage),
+    Edit(Uuid, EditMessage),
     NewList(NewListMessage),
     AddingList,
-    Select(usize),
+    Select(Uuid),
+    SelectAll,
+    Grab(Uuid),
+    /// Something was dropped onto the list
+    Drop(Uuid),
+    /// Moves the first list to where the second one is
+    Move(Uuid, Uuid),
 }
 
 #[derive(Debug, Clone)]
@@ -58,10 +69,16 @@ impl ListsBar {
     }
 }
 
+/// The input for adding a list
+pub fn new_list_id() -> text_input::Id {
+    text_input::Id::new("new_list")
+}
+
 impl Todo {
-    pub fn lists_bar(&self) -> Element<Message> {
+    pub fn lists_bar(&self) -> Element<'_, Message> {
         let add_new: Element<_> = if self.lists_bar.is_adding_list {
             text_input("Add a todo list", &self.lists_bar.new_list_input)
+                .id(new_list_id())
                 .on_input(|input| {
                     Message::ListsBar(ListsBarMessage::NewList(NewListMessage::Input(input)))
                 })
@@ -86,25 +103,50 @@ impl Todo {
             .into()
         };
 
-        let lists =
-            Column::with_children(self.todo_lists.iter().enumerate().map(|(index, list)| {
-                if Some(index) == self.current_list {
-                    list.view_bar_current(list.is_editing, index)
-                        .map(Message::ListsBar)
-                } else {
-                    list.view_bar(index).map(Message::ListsBar)
-                }
-            }));
+        let all_lists = (!self.todo_lists.is_empty()).then(|| {
+            let all_lists = button("All").on_press(Message::ListsBar(ListsBarMessage::SelectAll));
+
+            if self.current_list == Some(ListSelection::All) {
+                all_lists.style(styling::button::Button::Text)
+            } else {
+                all_lists.style(ButtonTheme::Text)
+            }
+        });
+
+        let lists = Column::with_children(self.todo_lists.iter().map(|list| {
+            let uid = list.list.uid;
+            let entry = if Some(ListSelection::List(uid)) == self.current_list {
+                list.view_bar_current(list.is_editing)
+            } else {
+                list.view_bar()
+            };
+
+            drag::drop_target(
+                row![drag::grip(ListsBarMessage::Grab(uid)), entry]
+                    .spacing(5)
+                    .align_items(Alignment::Center),
+                ListsBarMessage::Drop(uid),
+                self.drag.is_some(),
+            )
+            .map(Message::ListsBar)
+        }));
 
         container(
-            container(scrollable(
-                column![add_new, lists]
-                    .padding(15)
-                    .spacing(15)
-                    .width(SIDEBAR_WIDTH),
-            ))
-            .style(styling::container::Container::ListsBar)
-            .height(Length::Fill),
+            column![
+                container(self.workspace_selector()).width(SIDEBAR_WIDTH),
+                container(self.search_bar()).width(SIDEBAR_WIDTH),
+                container(scrollable(
+                    column![add_new]
+                        .push_maybe(all_lists)
+                        .push(lists)
+                        .padding(15)
+                        .spacing(15)
+                        .width(SIDEBAR_WIDTH),
+                ))
+                .style(styling::container::Container::ListsBar)
+                .height(Length::Fill),
+            ]
+            .spacing(10),
         )
         .padding(10)
         .into()
@@ -112,29 +154,35 @@ impl Todo {
 
     pub fn update_lists_bar(&mut self, lists_bar_message: ListsBarMessage) -> Command<Message> {
         match lists_bar_message {
-            ListsBarMessage::Edit(index, edit_message) => match edit_message {
-                EditMessage::Name(edit) => {
-                    self.todo_lists[index].name = edit;
+            ListsBarMessage::Edit(uid, edit_message) => {
+                let Some(index) = self.list_index(uid) else {
+                    return Command::none();
+                };
 
-                    Command::none()
-                }
-                EditMessage::Delete => {
-                    self.todo_lists.remove(index);
-                    self.current_list = None;
-                    self.is_dirty = true;
+                match edit_message {
+                    EditMessage::Name(edit) => self.todo_lists[index].list.rename(&edit),
+                    EditMessage::Delete => {
+                        self.todo_lists.remove(index);
 
-                    Command::none()
-                }
-                EditMessage::Done => {
-                    self.todo_lists[index].is_editing = false;
-                    self.is_dirty = true;
+                        if self.current_list == Some(ListSelection::List(uid)) {
+                            self.current_list = None;
+                        }
 
-                    Command::none()
+                        self.is_dirty = true;
+                    }
+                    EditMessage::Done => {
+                        self.todo_lists[index].is_editing = false;
+                        self.is_dirty = true;
+                    }
                 }
-            },
-            ListsBarMessage::Regular(index, regular_message) => match regular_message {
+
+                Command::none()
+            }
+            ListsBarMessage::Regular(uid, regular_message) => match regular_message {
                 RegularMessage::StartEdit => {
-                    self.todo_lists[index].is_editing = true;
+                    if let Some(list) = self.list_mut(uid) {
+                        list.is_editing = true;
+                    }
 
                     Command::none()
                 }
@@ -143,7 +191,7 @@ impl Todo {
                 NewListMessage::Submit => {
                     if !&self.lists_bar.new_list_input.is_empty() {
                         self.todo_lists
-                            .push(TodoList::new(&self.lists_bar.new_list_input));
+                            .push(TodoListWidget::new(&self.lists_bar.new_list_input));
 
                         self.is_dirty = true;
                     }
@@ -158,40 +206,54 @@ impl Todo {
                     Command::none()
                 }
             },
-            ListsBarMessage::Select(index) => {
-                self.current_list = Some(index);
+            ListsBarMessage::Select(uid) => {
+                self.current_list = Some(ListSelection::List(uid));
+
+                Command::none()
+            }
+            ListsBarMessage::SelectAll => {
+                self.current_list = Some(ListSelection::All);
 
                 Command::none()
             }
             ListsBarMessage::AddingList => {
                 self.lists_bar.is_adding_list = true;
 
+                Command::none()
+            }
+            ListsBarMessage::Grab(uid) => self.update_drag(DragMessage::Grab(Drag::List(uid))),
+            ListsBarMessage::Drop(uid) => self.update_drag(DragMessage::DropOnList(uid)),
+            ListsBarMessage::Move(from, to) => {
+                self.move_list(from, to);
+
                 Command::none()
             }
         }
     }
 }
 
-impl TodoList {
-    pub fn view_bar_current(&self, is_editing: bool, index: usize) -> Element<ListsBarMessage> {
+impl TodoListWidget {
+    pub fn view_bar_current(&self, is_editing: bool) -> Element<'_, ListsBarMessage> {
+        let uid = self.list.uid;
+
         if is_editing {
             self.view_edit()
-                .map(move |message| ListsBarMessage::Edit(index, message))
+                .map(move |message| ListsBarMessage::Edit(uid, message))
         } else {
             self.view_regular()
-                .map(move |message| ListsBarMessage::Regular(index, message))
+                .map(move |message| ListsBarMessage::Regular(uid, message))
         }
     }
 
-    pub fn view_bar(&self, index: usize) -> Element<ListsBarMessage> {
-        button(&*self.name)
-            .on_press(ListsBarMessage::Select(index))
+    pub fn view_bar(&self) -> Element<'_, ListsBarMessage> {
+        button(&*self.list.name)
+            .on_press(ListsBarMessage::Select(self.list.uid))
             .style(ButtonTheme::Text)
             .into()
     }
 
-    fn view_regular(&self) -> Element<RegularMessage> {
-        let name = button(&*self.name).style(styling::button::Button::Text);
+    fn view_regular(&self) -> Element<'_, RegularMessage> {
+        let name = button(&*self.list.name).style(styling::button::Button::Text);
         let edit_button = button(IconType::Edit.get_text())
             .on_press(RegularMessage::StartEdit)
             .style(styling::button::Button::TextSecondary);
@@ -201,8 +263,8 @@ impl TodoList {
             .into()
     }
 
-    fn view_edit(&self) -> Element<EditMessage> {
-        let name_edit = text_input("", &self.name)
+    fn view_edit(&self) -> Element<'_, EditMessage> {
+        let name_edit = text_input("", &self.list.name)
             .on_input(EditMessage::Name)
             .on_submit(EditMessage::Done)
             .style(styling::text_input::TextInput);
@@ -1,3 +1,12 @@
+pub mod all_lists;
+pub mod drag;
+pub mod export;
 pub mod filter;
+pub mod import;
 pub mod lists_bar;
+pub mod open_file;
+pub mod recovery;
+pub mod search;
 pub mod todo;
+pub mod watch;
+pub mod workspaces;
@@ -0,0 +1,136 @@
+use std::path::{self, PathBuf};
+
+use async_std::task;
+use iced::{
+    widget::{button, row, text_input},
+    Command, Element, Length,
+};
+use iced_todo::{
+    persistance::{self, LoadError, PersistError, Persistance},
+    settings::Settings,
+};
+
+use crate::gui::{
+    app::{Message, Todo},
+    styling,
+};
+
+#[derive(Debug, Clone)]
+pub enum OpenFileMessage {
+    Start,
+    Input(String),
+    Submit,
+    Cancel,
+}
+
+/// The input for the path of the file to open
+pub fn open_file_id() -> text_input::Id {
+    text_input::Id::new("open_file")
+}
+
+impl Todo {
+    pub fn open_file_bar(&self) -> Option<Element<'_, Message>> {
+        let input = self.open_file.as_ref()?;
+
+        let path = text_input(
+            "Path to a todo file or a todo.txt, which is created if missing",
+            input,
+        )
+        .id(open_file_id())
+        .on_input(|input| Message::OpenFile(OpenFileMessage::Input(input)))
+        .on_submit(Message::OpenFile(OpenFileMessage::Submit))
+        .width(Length::Fill)
+        .style(styling::text_input::TextInput);
+        let open = button("Open")
+            .on_press(Message::OpenFile(OpenFileMessage::Submit))
+            .style(styling::button::Button::Rounded);
+        let cancel = button("Cancel")
+            .on_press(Message::OpenFile(OpenFileMessage::Cancel))
+            .style(styling::button::Button::TextSecondary);
+
+        Some(
+            row![path, open, cancel]
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .into(),
+        )
+    }
+
+    pub fn update_open_file(&mut self, open_file_message: OpenFileMessage) -> Command<Message> {
+        match open_file_message {
+            OpenFileMessage::Start => {
+                self.open_file = Some(String::new());
+
+                return text_input::focus(open_file_id());
+            }
+            OpenFileMessage::Input(input) => self.open_file = Some(input),
+            OpenFileMessage::Submit => {
+                let Some(input) = self
+                    .open_file
+                    .take()
+                    .filter(|input| !input.trim().is_empty())
+                else {
+                    return Command::none();
+                };
+
+                let path = PathBuf::from(input.trim());
+                self.open(path::absolute(&path).unwrap_or(path));
+            }
+            OpenFileMessage::Cancel => self.open_file = None,
+        }
+
+        Command::none()
+    }
+
+    /// Switches to the lists saved in another file, which is opened again on the next start
+    pub fn open(&mut self, path: PathBuf) {
+        // two saves at once would write over each other
+        if self.autosave.is_saving() {
+            self.autosave.open_after_save(path);
+
+            return;
+        }
+
+        // the delayed save would otherwise go to the new file, or nowhere
+        if self.is_dirty && self.conflict.is_none() {
+            if let Err(error) = task::block_on(Self::save_lists(self.lists())) {
+                self.status = Err(error);
+
+                return;
+            }
+        }
+
+        let previous = self.file.clone();
+        persistance::set_save_path(path.clone());
+
+        if path.exists() {
+            let (todo_lists, status, recovery) = Self::read_save_file();
+
+            // a newer version's file, or one which is not a save file, is left alone rather than
+            // being opened without its lists
+            if let Err(
+                error @ PersistError::Load(LoadError::TooNew(..) | LoadError::Unrecognized(..)),
+            ) = status
+            {
+                if let Some(previous) = previous {
+                    persistance::set_save_path(previous);
+                }
+
+                self.status = Err(error);
+
+                return;
+            }
+
+            self.reset(todo_lists, status, recovery);
+        } else {
+            self.reset(Vec::new(), Ok(format!("New file {}", path.display())), None);
+        }
+
+        let mut settings = Settings::load();
+        settings.file = Some(path);
+
+        if let Err(error) = settings.save() {
+            self.status = Err(error);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use iced::{
+    theme::Text,
+    widget::{button, column, container, horizontal_space, row, scrollable, text, Column},
+    Command, Element, Length,
+};
+use iced_todo::persistance::{Backup, PersistError};
+
+use crate::gui::{
+    app::{Message, Todo},
+    styling::{self, colors},
+};
+
+/// What happened to a save file that could not be parsed
+pub struct Recovery {
+    /// Where the save file was moved to
+    pub quarantined: Result<PathBuf, PersistError>,
+    /// How many lists and items could not be recovered
+    pub skipped: usize,
+    pub backups: Vec<Backup>,
+}
+
+#[derive(Debug, Clone)]
+pub enum RecoveryMessage {
+    Restore(Backup),
+    /// Carries on with what was recovered
+    Continue,
+}
+
+impl Todo {
+    /// Shown in place of everything else until the user picks what to carry on with
+    pub fn recovery_dialog(&self) -> Element<'_, Message> {
+        let Some(recovery) = &self.recovery else {
+            return column![].into();
+        };
+
+        let title = text("Save file unreadable").size(50);
+
+        let quarantined = text(match &recovery.quarantined {
+            Ok(path) => format!(
+                "The save file could not be parsed, so it was moved to {} to keep it from being saved over.",
+                path.display()
+            ),
+            Err(error) => format!(
+                "The save file could not be parsed, nor moved aside ({error}). It will be saved over once anything changes."
+            ),
+        });
+
+        let recovered = text(format!(
+            "{} list{} could be recovered{}.",
+            self.todo_lists.len(),
+            if self.todo_lists.len() != 1 { "s" } else { "" },
+            if recovery.skipped > 0 {
+                format!(", {} lists or items could not", recovery.skipped)
+            } else {
+                String::new()
+            }
+        ))
+        .style(Text::Color(colors::text::secondary()));
+
+        let backups = (!recovery.backups.is_empty()).then(|| {
+            let backups = recovery.backups.iter().map(|backup| {
+                row![
+                    text(backup.time.format("%Y-%m-%d %H:%M:%S")),
+                    horizontal_space(),
+                    button("Restore")
+                        .on_press(Message::Recovery(RecoveryMessage::Restore(backup.clone())))
+                        .style(styling::button::Button::Rounded),
+                ]
+                .align_items(iced::Alignment::Center)
+                .into()
+            });
+
+            column![
+                text("Or restore a backup instead"),
+                scrollable(Column::with_children(backups).spacing(10).padding(10))
+                    .width(Length::Fill),
+            ]
+            .spacing(10)
+        });
+
+        let continue_button = button("Continue with what was recovered")
+            .on_press(Message::Recovery(RecoveryMessage::Continue))
+            .style(styling::button::Button::Rounded);
+
+        container(
+            column![title, quarantined, recovered, continue_button]
+                .push_maybe(backups)
+                .padding(15)
+                .spacing(15)
+                .max_width(600)
+                .align_items(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .center_x()
+        .into()
+    }
+
+    pub fn update_recovery(&mut self, recovery_message: RecoveryMessage) -> Command<Message> {
+        match recovery_message {
+            RecoveryMessage::Restore(backup) => match backup.load_lists() {
+                Ok(lists) => {
+                    self.restore(lists);
+                    self.recovery = None;
+                }
+                Err(error) => self.status = Err(error),
+            },
+            RecoveryMessage::Continue => {
+                // what was recovered goes into a new save file, unless that would save over the
+                // unreadable one
+                if self
+                    .recovery
+                    .take()
+                    .is_some_and(|recovery| recovery.quarantined.is_ok())
+                {
+                    self.is_dirty = true;
+                }
+            }
+        }
+
+        Command::none()
+    }
+}
@@ -0,0 +1,124 @@
+use chrono::Local;
+use iced::{
+    theme::Text,
+    widget::{button, column, horizontal_space, row, scrollable, text, text_input, Column},
+    Command, Element, Length,
+};
+use iced_todo::model::Query;
+use uuid::Uuid;
+
+use crate::gui::{
+    app::{ListSelection, Message, Todo},
+    styling::{self, colors},
+};
+
+use super::filter::Filter;
+
+#[derive(Debug, Clone)]
+pub enum SearchMessage {
+    Input(String),
+    /// Shows the item or subtask with the second uid, in the list with the first
+    Jump(Uuid, Uuid),
+}
+
+impl Todo {
+    pub fn search_bar(&self) -> Element<'_, Message> {
+        text_input("Search", &self.search)
+            .on_input(|input| Message::Search(SearchMessage::Input(input)))
+            .width(Length::Fill)
+            .style(styling::text_input::TextInput)
+            .into()
+    }
+
+    /// Every item matching the search, along with the name of its list and of the item subtasks
+    /// are under
+    pub fn search_results(&self) -> Element<'_, Message> {
+        let query = Query::new(&self.search);
+        let title = text("Search").size(50);
+
+        let results = self
+            .todo_lists
+            .iter()
+            .flat_map(|list| {
+                list.list
+                    .search(&query)
+                    .map(move |(path, item)| (&list.list, path, item))
+            })
+            .map(|(list, path, item)| {
+                let place = match path.subtask.and(list.get(path.index)) {
+                    Some(parent) => format!("{} › {}", list.name, parent.name),
+                    None => list.name.clone(),
+                };
+
+                button(row![
+                    text(&item.name),
+                    horizontal_space(),
+                    text(place).style(Text::Color(colors::text::secondary())),
+                ])
+                .on_press(Message::Search(SearchMessage::Jump(list.uid, item.uid)))
+                .width(Length::Fill)
+                .style(styling::button::Button::Text)
+                .into()
+            })
+            .collect::<Vec<Element<_>>>();
+
+        let results: Element<_> = if results.is_empty() {
+            text("Nothing found")
+                .size(30)
+                .style(Text::Color(colors::text::secondary()))
+                .into()
+        } else {
+            scrollable(Column::with_children(results).spacing(10).padding(10)).into()
+        };
+
+        column![title, results]
+            .padding(15)
+            .spacing(15)
+            .align_items(iced::Alignment::Center)
+            .into()
+    }
+
+    pub fn update_search(&mut self, search_message: SearchMessage) -> Command<Message> {
+        match search_message {
+            SearchMessage::Input(input) => {
+                self.search = input;
+
+                Command::none()
+            }
+            SearchMessage::Jump(list_uid, item) => {
+                let filter = self.filter.clone();
+                let sort = self.sort;
+                let Some(list) = self.list_mut(list_uid) else {
+                    return Command::none();
+                };
+                // subtasks are shown under their item, which is what is scrolled to
+                let Some(shown) = list
+                    .list
+                    .find(item)
+                    .and_then(|path| list.list.get(path.index))
+                    .map(|item| item.uid)
+                else {
+                    return Command::none();
+                };
+                // the item has to be shown to be scrolled to
+                let filter = if list
+                    .shown_items(&filter, sort, Local::now().naive_local())
+                    .contains(&shown)
+                {
+                    filter
+                } else {
+                    Filter::All
+                };
+
+                list.selected_item = Some(item);
+                let command = list.scroll_to(&filter, sort, shown);
+
+                self.filter = filter;
+                self.current_list = Some(ListSelection::List(list_uid));
+                self.search.clear();
+
+                command
+            }
+        }
+    }
+}
@@ -1,26 +1,26 @@
+use chrono::{Local, NaiveDate, NaiveTime, Utc};
 use iced::{
-    widget::{button, checkbox, horizontal_space, row, text, text_input},
-    Command, Element,
+    theme::Text,
+    widget::{button, checkbox, column, horizontal_space, row, text, text_input, tooltip},
+    Element,
+};
+use iced_todo::model::{
+    todo_item::{DATE_FORMAT, TIME_FORMAT},
+    TodoItem,
+};
+use uuid::Uuid;
+
+use crate::{
+    gui::{
+        icons::IconType,
+        styling::{self, colors},
+        widgets::drag,
+    },
+    utils::relative_time,
 };
-
-use serde::{Deserialize, Serialize};
-
-use crate::gui::{app::Message, icons::IconType, styling};
 
 use super::todo_list::TodoListMessage;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TodoItem {
-    pub completed: bool,
-    pub name: String,
-
-    #[serde(skip)]
-    pub editing: bool,
-
-    #[serde(skip)]
-    pub is_dirty: bool,
-}
-
 #[derive(Debug, Clone)]
 pub enum ItemMessage {
     Edit(EditMessage),
@@ -30,6 +30,10 @@ pub enum ItemMessage {
 #[derive(Debug, Clone)]
 pub enum EditMessage {
     Name(String),
+    DueDate(String),
+    DueTime(String),
+    SubtaskInput(String),
+    AddSubtask,
     Delete,
     Done,
 }
@@ -38,96 +42,171 @@ pub enum EditMessage {
 pub enum RegularMessage {
     Completed(bool),
     StartEdit,
+    Grab,
 }
 
-impl Default for TodoItem {
-    fn default() -> Self {
-        Self {
-            completed: false,
-            name: "TodoItem".to_owned(),
-            editing: false,
-            is_dirty: false,
-        }
-    }
+/// The item being edited, along with what has been typed into its due date and subtask inputs
+#[derive(Debug, Clone)]
+pub struct ItemEdit {
+    pub uid: Uuid,
+    pub due_date: String,
+    pub due_time: String,
+    pub subtask_input: String,
 }
 
-impl TodoItem {
-    pub fn new(name: &str) -> Self {
+impl ItemEdit {
+    pub fn new(item: &TodoItem) -> Self {
         Self {
-            name: name.to_string(),
-            ..Self::default()
+            uid: item.uid,
+            subtask_input: String::new(),
+            due_date: item
+                .due_date
+                .map(|date| date.format(DATE_FORMAT).to_string())
+                .unwrap_or_default(),
+            due_time: item
+                .due_time
+                .map(|time| time.format(TIME_FORMAT).to_string())
+                .unwrap_or_default(),
         }
     }
 
-    pub fn update(&mut self, message: ItemMessage) -> Command<Message> {
-        match message {
-            ItemMessage::Edit(edit_message) => match edit_message {
-                EditMessage::Name(name) => {
-                    self.name = name;
-                    self.is_dirty = true;
-
-                    Command::none()
-                }
-                EditMessage::Done => {
-                    self.editing = false;
-                    self.is_dirty = true;
-
-                    Command::none()
-                }
-                _ => Command::none(),
-            },
-            ItemMessage::Regular(regular_message) => match regular_message {
-                RegularMessage::Completed(completed) => {
-                    self.completed = completed;
-                    self.is_dirty = true;
-
-                    Command::none()
-                }
-                RegularMessage::StartEdit => {
-                    self.editing = true;
-
-                    Command::none()
-                }
-            },
-        }
+    /// The typed due date, or `None` while either input can't be parsed
+    pub fn parse_due(&self) -> Option<(Option<NaiveDate>, Option<NaiveTime>)> {
+        let date = match self.due_date.trim() {
+            "" => None,
+            date => Some(NaiveDate::parse_from_str(date, DATE_FORMAT).ok()?),
+        };
+        let time = match self.due_time.trim() {
+            "" => None,
+            time => Some(NaiveTime::parse_from_str(time, TIME_FORMAT).ok()?),
+        };
+
+        Some((date, time))
     }
+}
 
-    pub fn view(&self, index: usize) -> Element<TodoListMessage> {
-        if self.editing {
-            self.view_edit()
-                .map(move |message| TodoListMessage::Item(index, ItemMessage::Edit(message)))
-        } else {
-            self.view_regular()
-                .map(move |message| TodoListMessage::Item(index, ItemMessage::Regular(message)))
-        }
+/// The name input of the item being edited
+pub fn name_input_id() -> text_input::Id {
+    text_input::Id::new("item_name")
+}
+
+pub fn view<'a>(
+    item: &'a TodoItem,
+    is_subtask: bool,
+    edit: Option<&'a ItemEdit>,
+) -> Element<'a, TodoListMessage> {
+    let uid = item.uid;
+
+    if let Some(edit) = edit {
+        view_edit(item, is_subtask, edit)
+            .map(move |message| TodoListMessage::Item(uid, ItemMessage::Edit(message)))
+    } else {
+        view_regular(item, is_subtask)
+            .map(move |message| TodoListMessage::Item(uid, ItemMessage::Regular(message)))
     }
+}
 
-    fn view_regular(&self) -> Element<RegularMessage> {
-        row![
-            checkbox("", self.completed)
+fn view_regular(item: &TodoItem, is_subtask: bool) -> Element<'_, RegularMessage> {
+    let due = item.due_text().map(|due| {
+        text(due).style(Text::Color(
+            if item.is_overdue(Local::now().naive_local()) {
+                colors::accents::danger()
+            } else {
+                colors::text::secondary()
+            },
+        ))
+    });
+    let priority = item.priority.map(|priority| {
+        text(format!("({priority})")).style(Text::Color(colors::text::secondary()))
+    });
+    let progress = item.progress().map(|(completed, total)| {
+        text(format!("{completed}/{total}")).style(Text::Color(colors::text::secondary()))
+    });
+    // only whole items can be dragged, subtasks stay with their item
+    let grip = (!is_subtask).then(|| drag::grip(RegularMessage::Grab));
+    let name: Element<_> = match times_text(item) {
+        Some(times) => tooltip(
+            text(&item.name),
+            text(times).size(14),
+            tooltip::Position::Bottom,
+        )
+        .gap(5)
+        .padding(5)
+        .style(styling::container::Container::Tooltip)
+        .into(),
+        None => text(&item.name).into(),
+    };
+
+    row![]
+        .push_maybe(grip)
+        .push(
+            checkbox("", item.completed)
                 .on_toggle(RegularMessage::Completed)
                 .style(styling::checkbox::Checkbox),
-            text(&self.name),
-            horizontal_space(),
+        )
+        .push_maybe(priority)
+        .push(name)
+        .push(horizontal_space())
+        .push_maybe(progress)
+        .push_maybe(due)
+        .push(
             button(IconType::Edit.get_text())
                 .on_press(RegularMessage::StartEdit)
                 .style(styling::button::Button::TextSecondary),
-        ]
+        )
         .align_items(iced::Alignment::Center)
         .into()
-    }
+}
 
-    fn view_edit(&self) -> Element<EditMessage> {
-        row![
-            text_input("", &self.name)
-                .on_input(EditMessage::Name)
-                .on_submit(EditMessage::Done)
-                .style(styling::text_input::TextInput),
-            button(IconType::Delete.get_text())
-                .on_press(EditMessage::Delete)
-                .style(styling::button::Button::DangerText),
-        ]
-        .spacing(10)
-        .into()
-    }
+/// When the item was created, last changed and completed, as far as it is known
+fn times_text(item: &TodoItem) -> Option<String> {
+    let now = Utc::now();
+    let times: Vec<_> = [
+        ("Created", item.created_at),
+        ("Updated", item.updated_at),
+        ("Completed", item.completed_at.filter(|_| item.completed)),
+    ]
+    .into_iter()
+    .filter_map(|(name, time)| Some(format!("{name} {}", relative_time(time?, now))))
+    .collect();
+
+    (!times.is_empty()).then(|| times.join(" · "))
+}
+
+fn view_edit<'a>(
+    item: &'a TodoItem,
+    is_subtask: bool,
+    edit: &'a ItemEdit,
+) -> Element<'a, EditMessage> {
+    let edit_row = row![
+        text_input("", &item.name)
+            .id(name_input_id())
+            .on_input(EditMessage::Name)
+            .on_submit(EditMessage::Done)
+            .style(styling::text_input::TextInput),
+        text_input("YYYY-MM-DD", &edit.due_date)
+            .on_input(EditMessage::DueDate)
+            .on_submit(EditMessage::Done)
+            .width(130)
+            .style(styling::text_input::TextInput),
+        text_input("HH:MM", &edit.due_time)
+            .on_input(EditMessage::DueTime)
+            .on_submit(EditMessage::Done)
+            .width(80)
+            .style(styling::text_input::TextInput),
+        button(IconType::Delete.get_text())
+            .on_press(EditMessage::Delete)
+            .style(styling::button::Button::DangerText),
+    ]
+    .spacing(10);
+
+    // subtasks don't have subtasks of their own
+    let new_subtask = (!is_subtask).then(|| {
+        text_input("Add a subtask", &edit.subtask_input)
+            .on_input(EditMessage::SubtaskInput)
+            .on_submit(EditMessage::AddSubtask)
+            .style(styling::text_input::TextInput)
+    });
+
+    column![edit_row].push_maybe(new_subtask).spacing(10).into()
 }
@@ -1,141 +1,300 @@
+use chrono::{Local, NaiveDateTime};
 use iced::{
     theme::Text,
-    widget::{column, container, scrollable, text, text_input, vertical_space, Column},
-    Command, Element,
+    widget::{
+        column, container, scrollable, scrollable::RelativeOffset, text, text_input,
+        vertical_space, Column,
+    },
+    Command, Element, Padding,
 };
-use serde::{Deserialize, Serialize};
+
+use iced_todo::model::{ItemPath, Sort, TodoItem, TodoList};
+use uuid::Uuid;
 
 use crate::{
     gui::{
         app::Message,
         styling::{self, colors},
-        widgets::filter::Filter,
+        widgets::{drag, filter::Filter},
     },
-    utils::{check_dirty, strip_trailing_newline},
+    utils::strip_trailing_newline,
 };
 
-use super::todo_item::{EditMessage, ItemMessage, TodoItem};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TodoList {
-    pub todo_items: Vec<TodoItem>,
-    pub name: String,
+use super::todo_item::{self, EditMessage, ItemEdit, ItemMessage, RegularMessage};
 
-    #[serde(skip)]
+/// A [`TodoList`] along with the state needed to show and edit it
+#[derive(Debug, Clone)]
+pub struct TodoListWidget {
+    pub list: TodoList,
     pub input: String,
-
-    #[serde(skip)]
     pub is_dirty: bool,
-
-    #[serde(skip)]
     pub is_editing: bool,
+    pub editing_item: Option<ItemEdit>,
+    pub selected_item: Option<Uuid>,
 }
 
+/// Items are found by their uid, whatever has happened to the list since the message was sent
 #[derive(Debug, Clone)]
 pub enum TodoListMessage {
-    Item(usize, ItemMessage),
+    Item(Uuid, ItemMessage),
+    /// Moves the first item to where the second one is
+    Move(Uuid, Uuid),
+    /// Something was dropped onto the item
+    Drop(Uuid),
     InputEdit(String),
     NewSubmitted,
 }
 
-impl Default for TodoList {
-    fn default() -> Self {
+impl From<TodoList> for TodoListWidget {
+    fn from(list: TodoList) -> Self {
         Self {
-            todo_items: Vec::new(),
-            name: "TodoList".to_owned(),
+            list,
             input: String::new(),
             is_dirty: false,
             is_editing: false,
+            editing_item: None,
+            selected_item: None,
         }
     }
 }
 
-impl TodoList {
+impl TodoListWidget {
     pub fn new(name: &str) -> Self {
-        Self {
-            name: name.to_string(),
-            ..Self::default()
-        }
-    }
-
-    pub fn add(&mut self, name: &str) {
-        self.todo_items.push(TodoItem::new(name));
+        TodoList::new(name).into()
     }
 
     pub fn update(&mut self, message: TodoListMessage) -> Command<Message> {
-        let command = match message {
+        match message {
             TodoListMessage::InputEdit(action) => {
                 self.input = action;
-
-                Command::none()
             }
             TodoListMessage::NewSubmitted => {
                 if !self.input.is_empty() {
                     let text = strip_trailing_newline(&self.input);
-                    self.add(&text);
+                    self.list.add(&text);
                     self.input = "".to_owned();
                 }
 
                 self.is_dirty = true;
+            }
+            TodoListMessage::Item(uid, item_message) => {
+                let Some(path) = self.list.find(uid) else {
+                    return Command::none();
+                };
 
-                Command::none()
+                self.update_item(path, item_message);
             }
-            TodoListMessage::Item(index, item_message) => match item_message {
-                ItemMessage::Edit(EditMessage::Delete) => {
-                    self.todo_items.remove(index);
+            TodoListMessage::Move(from, to) => {
+                let (Some(from_path), Some(to_path)) = (self.list.find(from), self.list.find(to))
+                else {
+                    return Command::none();
+                };
 
+                // subtasks stay with their item
+                if from_path.subtask.is_none()
+                    && to_path.subtask.is_none()
+                    && self
+                        .list
+                        .move_item(from_path.index, to_path.index)
+                        .is_some()
+                {
+                    self.editing_item = None;
+                    self.selected_item = Some(from);
                     self.is_dirty = true;
+                }
+            }
+            TodoListMessage::Drop(_) => {}
+        }
+
+        Command::none()
+    }
 
-                    Command::none()
+    fn update_item(&mut self, path: ItemPath, item_message: ItemMessage) {
+        match item_message {
+            ItemMessage::Edit(EditMessage::Name(name)) => {
+                self.list.rename_item(path, &name);
+                self.is_dirty = true;
+            }
+            ItemMessage::Edit(EditMessage::DueDate(input)) => {
+                if let Some(edit) = &mut self.editing_item {
+                    edit.due_date = input;
                 }
-                _ => self.todo_items.get_mut(index).unwrap().update(item_message),
-            },
-        };
 
-        self.is_dirty = check_dirty(&self.is_dirty, &self.todo_items, |item| item.is_dirty);
+                self.apply_due(path);
+            }
+            ItemMessage::Edit(EditMessage::DueTime(input)) => {
+                if let Some(edit) = &mut self.editing_item {
+                    edit.due_time = input;
+                }
 
-        command
+                self.apply_due(path);
+            }
+            ItemMessage::Edit(EditMessage::SubtaskInput(input)) => {
+                if let Some(edit) = &mut self.editing_item {
+                    edit.subtask_input = input;
+                }
+            }
+            ItemMessage::Edit(EditMessage::AddSubtask) => {
+                if let Some(edit) = &mut self.editing_item {
+                    if !edit.subtask_input.is_empty() {
+                        let text = strip_trailing_newline(&edit.subtask_input);
+                        self.list.add_subtask(path.index, &text);
+                        edit.subtask_input.clear();
+                        self.is_dirty = true;
+                    }
+                }
+            }
+            ItemMessage::Edit(EditMessage::Delete) => {
+                let removed = self.list.remove(path);
+                self.editing_item = None;
+
+                if removed.is_some_and(|removed| self.selected_item == Some(removed.uid)) {
+                    self.selected_item = None;
+                }
+
+                self.is_dirty = true;
+            }
+            ItemMessage::Edit(EditMessage::Done) => {
+                self.editing_item = None;
+                self.is_dirty = true;
+            }
+            ItemMessage::Regular(RegularMessage::Completed(completed)) => {
+                self.list.set_completed(path, completed);
+                self.is_dirty = true;
+            }
+            ItemMessage::Regular(RegularMessage::StartEdit) => {
+                self.editing_item = self.list.get(path).map(ItemEdit::new);
+            }
+            // dragging is taken care of by `Todo`, as items can be dragged between lists
+            ItemMessage::Regular(RegularMessage::Grab) => {}
+        }
+    }
+
+    /// Sets the due date of the item being edited, once what has been typed is valid
+    fn apply_due(&mut self, path: ItemPath) {
+        if let Some((date, time)) = self.editing_item.as_ref().and_then(ItemEdit::parse_due) {
+            self.list.set_due(path, date, time);
+            self.is_dirty = true;
+        }
+    }
+
+    /// The uids of the items that pass the filter, in the order they are shown in
+    pub fn shown_items(&self, filter: &Filter, sort: Sort, now: NaiveDateTime) -> Vec<Uuid> {
+        sort.sorted(&self.list.todo_items)
+            .into_iter()
+            .filter(|(_, item)| filter.filter(item, now))
+            .map(|(_, item)| item.uid)
+            .collect()
     }
 
-    pub fn view(&self, filter: &Filter) -> Element<TodoListMessage> {
-        let title = text(&self.name).size(50);
+    /// Scrolls the items so that the item is in view
+    pub fn scroll_to(&self, filter: &Filter, sort: Sort, uid: Uuid) -> Command<Message> {
+        let shown = self.shown_items(filter, sort, Local::now().naive_local());
+
+        match shown.iter().position(|shown| *shown == uid) {
+            Some(position) if shown.len() > 1 => scrollable::snap_to(
+                items_id(),
+                RelativeOffset {
+                    x: 0.0,
+                    y: position as f32 / (shown.len() - 1) as f32,
+                },
+            ),
+            _ => Command::none(),
+        }
+    }
+
+    fn view_item<'a>(
+        &'a self,
+        item: &'a TodoItem,
+        is_subtask: bool,
+    ) -> Element<'a, TodoListMessage> {
+        let edit = self
+            .editing_item
+            .as_ref()
+            .filter(|edit| edit.uid == item.uid);
+
+        todo_item::view(item, is_subtask, edit)
+    }
+
+    /// The subtask, marked when it is the selected item, like after jumping to it from a search
+    fn view_subtask<'a>(&'a self, subtask: &'a TodoItem) -> Element<'a, TodoListMessage> {
+        let view = self.view_item(subtask, true);
+
+        if self.selected_item == Some(subtask.uid) {
+            container(view)
+                .padding(5)
+                .style(styling::container::Container::CurrentItem)
+                .into()
+        } else {
+            view
+        }
+    }
+
+    /// The items that pass the filter, in the order they are sorted in
+    pub fn view_items(
+        &self,
+        filter: &Filter,
+        sort: Sort,
+        now: NaiveDateTime,
+        is_dragging: bool,
+    ) -> Vec<Element<'_, TodoListMessage>> {
+        sort.sorted(&self.list.todo_items)
+            .into_iter()
+            .filter(|(_, item)| filter.filter(item, now))
+            .map(|(_, item)| {
+                let uid = item.uid;
+                // subtasks are shown under their item whatever the filter
+                let subtasks = item
+                    .subtasks
+                    .iter()
+                    .map(|subtask| self.view_subtask(subtask));
+
+                let item: Element<_> = column![self.view_item(item, false)]
+                    .push(
+                        Column::with_children(subtasks)
+                            .spacing(10)
+                            .padding(Padding::from([0, 0, 0, 30])),
+                    )
+                    .spacing(10)
+                    .into();
+
+                let item = if self.selected_item == Some(uid) {
+                    container(item)
+                        .padding(5)
+                        .style(styling::container::Container::CurrentItem)
+                        .into()
+                } else {
+                    item
+                };
+
+                drag::drop_target(item, TodoListMessage::Drop(uid), is_dragging)
+            })
+            .collect()
+    }
+
+    pub fn view(
+        &self,
+        filter: &Filter,
+        sort: Sort,
+        is_dragging: bool,
+    ) -> Element<'_, TodoListMessage> {
+        let title = text(&self.list.name).size(50);
 
         let new_todo = text_input("Input Todo", &self.input)
+            .id(new_item_id())
             .on_input(TodoListMessage::InputEdit)
             .on_submit(TodoListMessage::NewSubmitted)
             .style(styling::text_input::TextInput);
 
         let todo_items: Element<_> = {
-            let filtered = self
-                .todo_items
-                .iter()
-                .filter(|item| filter.filter(item))
-                .collect::<Vec<_>>();
-
-            let items = filtered
-                .iter()
-                .enumerate()
-                .map(|(index, item)| item.view(index))
-                .collect::<Vec<_>>();
+            let items = self.view_items(filter, sort, Local::now().naive_local(), is_dragging);
 
             if items.is_empty() {
-                container(column![
-                    vertical_space(),
-                    text(match filter {
-                        Filter::All => "Add a new item todo",
-                        Filter::Uncomplete => "Nothing Todo!",
-                        Filter::Completed => "Nothing Completed...",
-                    })
-                    .size(30)
-                    .style(Text::Color(colors::text::secondary())),
-                    vertical_space(),
-                ])
-                .align_x(iced::alignment::Horizontal::Center)
-                .align_y(iced::alignment::Vertical::Center)
-                .padding(10)
-                .into()
+                empty_view(filter)
             } else {
-                scrollable(Column::with_children(items).spacing(10).padding(10)).into()
+                scrollable(Column::with_children(items).spacing(10).padding(10))
+                    .id(items_id())
+                    .into()
             }
         };
 
@@ -146,3 +305,28 @@ impl TodoList {
             .into()
     }
 }
+
+/// The scrollable holding the items of the current list
+pub fn items_id() -> scrollable::Id {
+    scrollable::Id::new("todo_items")
+}
+
+/// The input for adding items to the current list
+pub fn new_item_id() -> text_input::Id {
+    text_input::Id::new("new_todo")
+}
+
+/// Shown in place of the items when none pass the filter
+pub fn empty_view<'a, M: 'a>(filter: &Filter) -> Element<'a, M> {
+    container(column![
+        vertical_space(),
+        text(filter.empty_message())
+            .size(30)
+            .style(Text::Color(colors::text::secondary())),
+        vertical_space(),
+    ])
+    .align_x(iced::alignment::Horizontal::Center)
+    .align_y(iced::alignment::Vertical::Center)
+    .padding(10)
+    .into()
+}
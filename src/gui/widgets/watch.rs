@@ -0,0 +1,174 @@
+use std::{fs, path::Path, time::Duration, time::SystemTime};
+
+use async_std::task;
+use iced::{
+    subscription,
+    theme::Text,
+    widget::{button, column, container, row, text},
+    Command, Element, Length, Subscription,
+};
+use iced_todo::{
+    formats::{todotxt, Format},
+    model::TodoList,
+    persistance::{self, Persistance},
+};
+
+use crate::gui::{
+    app::{Message, Todo},
+    styling::{self, colors},
+};
+
+/// How often the save file is checked for changes made by something else
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// When the save file was last changed and how long it was, which tells when it has changed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl FileStamp {
+    pub fn read(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ConflictMessage {
+    /// Replaces the lists with the ones in the file
+    LoadFile,
+    /// Saves the lists over the file
+    KeepMine,
+}
+
+pub fn subscription() -> Subscription<Message> {
+    subscription::unfold("watch_save_file", (), |()| async {
+        task::sleep(POLL_INTERVAL).await;
+
+        (Message::PollFile, ())
+    })
+}
+
+impl Todo {
+    /// Reloads the save file if something else changed it, unless that would lose unsaved changes
+    pub fn poll_file(&mut self) -> Command<Message> {
+        // a running save changes the file itself, which is only synced once it is done
+        if self.conflict.is_some() || self.recovery.is_some() || self.autosave.is_saving() {
+            return Command::none();
+        }
+
+        let stamp = self.file.as_deref().and_then(FileStamp::read);
+
+        // a missing file is written again by the next save
+        if stamp == self.stamp || stamp.is_none() {
+            return Command::none();
+        }
+
+        self.stamp = stamp;
+
+        let loaded = Self::load_lists().map(|mut lists| {
+            // todo.txt has no uids, so the lists and items are matched with the ones shown
+            if self.file.as_deref().is_some_and(persistance::is_todo_txt) {
+                todotxt::keep_uids(&self.lists(), &mut lists);
+            }
+
+            lists
+        });
+
+        match loaded {
+            // the change was our own save
+            Ok(lists) if self.is_synced(&lists) => {}
+            Ok(lists) if self.lists() == self.synced => self.reload(lists),
+            Ok(lists) => self.conflict = Some(lists),
+            Err(error) => self.status = Err(error),
+        }
+
+        Command::none()
+    }
+
+    /// Whether the lists are the last ones saved, as far as the save file can hold them
+    fn is_synced(&self, lists: &[TodoList]) -> bool {
+        if lists == self.synced {
+            return true;
+        }
+
+        // todo.txt has no subtasks, empty lists nor uids, which are lost by saving
+        self.file.as_deref().is_some_and(persistance::is_todo_txt)
+            && Format::TodoTxt.export(lists) == Format::TodoTxt.export(&self.synced)
+    }
+
+    /// Takes in the lists from the file, which are then already saved
+    fn reload(&mut self, lists: Vec<TodoList>) {
+        self.restore(lists);
+        self.synced = self.lists();
+        self.is_dirty = false;
+
+        for list in &mut self.todo_lists {
+            list.is_dirty = false;
+        }
+
+        self.status = Ok("Reloaded after the save file changed".to_owned());
+    }
+
+    /// Shown in place of everything else when the file changed while there were unsaved changes
+    pub fn conflict_dialog(&self) -> Element<'_, Message> {
+        let title = text("Save file changed").size(50);
+        let explanation = text(
+            "Something else changed the save file while you had changes that were not saved yet.",
+        );
+        let lists = text(format!(
+            "The file has {} lists, you have {}.",
+            self.conflict.as_ref().map_or(0, Vec::len),
+            self.todo_lists.len()
+        ))
+        .style(Text::Color(colors::text::secondary()));
+
+        let load_file = button("Load the file")
+            .on_press(Message::Conflict(ConflictMessage::LoadFile))
+            .style(styling::button::Button::Rounded);
+        let keep_mine = button("Keep mine and save over it")
+            .on_press(Message::Conflict(ConflictMessage::KeepMine))
+            .style(styling::button::Button::DangerText);
+
+        container(
+            column![
+                title,
+                explanation,
+                lists,
+                row![load_file, keep_mine].spacing(15)
+            ]
+            .padding(15)
+            .spacing(15)
+            .max_width(600)
+            .align_items(iced::Alignment::Center),
+        )
+        .width(Length::Fill)
+        .center_x()
+        .into()
+    }
+
+    pub fn update_conflict(&mut self, conflict_message: ConflictMessage) -> Command<Message> {
+        let Some(lists) = self.conflict.take() else {
+            return Command::none();
+        };
+
+        match conflict_message {
+            ConflictMessage::LoadFile => {
+                self.reload(lists);
+
+                Command::none()
+            }
+            ConflictMessage::KeepMine => {
+                self.is_dirty = true;
+
+                self.schedule_save()
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use iced::{
+    widget::{button, pick_list, row, text_input},
+    Command, Element, Length,
+};
+use iced_todo::settings::{Settings, Workspace};
+
+use crate::gui::{
+    app::{Message, Todo},
+    styling,
+};
+
+#[derive(Debug, Clone)]
+pub enum WorkspaceMessage {
+    Select(Workspace),
+    Adding,
+    Input(String),
+    Submit,
+}
+
+/// The input for naming a new workspace
+pub fn new_workspace_id() -> text_input::Id {
+    text_input::Id::new("new_workspace")
+}
+
+impl Todo {
+    /// Picks between the workspaces, shown above the lists
+    pub fn workspace_selector(&self) -> Element<'_, Message> {
+        if let Some(input) = &self.new_workspace {
+            return text_input("Name the workspace", input)
+                .id(new_workspace_id())
+                .on_input(|input| Message::Workspace(WorkspaceMessage::Input(input)))
+                .on_submit(Message::Workspace(WorkspaceMessage::Submit))
+                .width(Length::Fill)
+                .style(styling::text_input::TextInput)
+                .into();
+        }
+
+        // a file opened by hand may not belong to any workspace
+        let current = self
+            .workspaces
+            .iter()
+            .find(|workspace| Some(&workspace.file) == self.file.as_ref())
+            .cloned();
+
+        let workspaces = pick_list(self.workspaces.as_slice(), current, |workspace| {
+            Message::Workspace(WorkspaceMessage::Select(workspace))
+        })
+        .placeholder("Other file")
+        .width(Length::Fill);
+
+        let add = button("+")
+            .on_press(Message::Workspace(WorkspaceMessage::Adding))
+            .style(styling::button::Button::Rounded);
+
+        row![workspaces, add]
+            .spacing(5)
+            .align_items(iced::Alignment::Center)
+            .into()
+    }
+
+    pub fn update_workspace(&mut self, workspace_message: WorkspaceMessage) -> Command<Message> {
+        match workspace_message {
+            WorkspaceMessage::Select(workspace) => {
+                if self.file.as_ref() != Some(&workspace.file) {
+                    self.open(workspace.file);
+                }
+            }
+            WorkspaceMessage::Adding => {
+                self.new_workspace = Some(String::new());
+
+                return text_input::focus(new_workspace_id());
+            }
+            WorkspaceMessage::Input(input) => self.new_workspace = Some(input),
+            WorkspaceMessage::Submit => {
+                let Some(name) = self.new_workspace.take() else {
+                    return Command::none();
+                };
+                let name = name.trim();
+
+                if name.is_empty() {
+                    return Command::none();
+                }
+
+                let mut settings = Settings::load();
+
+                match settings.add_workspace(name) {
+                    Ok(Some(workspace)) => {
+                        self.workspaces = settings.workspaces();
+
+                        // saved before opening, which saves the settings again with the file
+                        match settings.save() {
+                            Ok(()) => self.open(workspace.file),
+                            Err(error) => self.status = Err(error),
+                        }
+                    }
+                    Ok(None) => {
+                        self.status = Ok(format!("There is already a workspace named {name}"))
+                    }
+                    Err(error) => self.status = Err(error),
+                }
+            }
+        }
+
+        Command::none()
+    }
+}
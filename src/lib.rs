@@ -0,0 +1,8 @@
+//! The todo data and how it is saved, usable without pulling in the iced gui
+
+pub mod formats;
+pub mod model;
+pub mod persistance;
+pub mod recovery;
+pub mod save_file;
+pub mod settings;
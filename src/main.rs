@@ -1,13 +1,43 @@
+use clap::Parser;
+use cli::{Args, Cli};
 use gui::app::Todo;
-use iced::{Application, Font, Settings};
+use iced::{window, Application, Font, Settings as IcedSettings};
+use iced_todo::{persistance, settings::Settings};
 
+mod cli;
 mod gui;
 mod utils;
 
 const SIDEBAR_WIDTH: u16 = 200;
 
 fn main() -> iced::Result {
-    Todo::run(Settings {
+    let args = Args::parse();
+
+    if let Some(file) = args.file {
+        persistance::set_save_path(file);
+    }
+
+    if let Some(name) = args.workspace {
+        match Settings::load().workspace(&name) {
+            Some(workspace) => persistance::set_save_path(workspace.file),
+            None => {
+                eprintln!("iced-todo: No workspace named \"{name}\"");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(command) = args.command {
+        if let Err(error) = Cli::run(command) {
+            cli::print_error(&error);
+
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
+    Todo::run(IcedSettings {
         default_font: Font::with_name("Montserrat"),
         fonts: vec![
             include_bytes!("../fonts/Montserrat-SemiBold.ttf")
@@ -15,6 +45,11 @@ fn main() -> iced::Result {
                 .into(),
             include_bytes!("../fonts/todo-icons.ttf").as_slice().into(),
         ],
-        ..Settings::default()
+        window: window::Settings {
+            // the lists are saved before closing
+            exit_on_close_request: false,
+            ..window::Settings::default()
+        },
+        ..IcedSettings::default()
     })
 }
@@ -0,0 +1,11 @@
+//! The todo data, free of any gui state so it can be used without iced
+
+pub mod search;
+pub mod sort;
+pub mod todo_item;
+pub mod todo_list;
+
+pub use search::Query;
+pub use sort::Sort;
+pub use todo_item::TodoItem;
+pub use todo_list::{ItemPath, TodoList};
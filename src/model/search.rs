@@ -0,0 +1,23 @@
+/// A case-insensitive search made of whitespace separated terms, all of which have to match
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    terms: Vec<String>,
+}
+
+impl Query {
+    pub fn new(query: &str) -> Self {
+        Self {
+            terms: query.split_whitespace().map(str::to_lowercase).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+
+        self.terms.iter().all(|term| text.contains(term.as_str()))
+    }
+}
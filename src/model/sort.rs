@@ -0,0 +1,87 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    fmt,
+    str::FromStr,
+};
+
+use super::todo_item::TodoItem;
+
+/// The order items are shown in, without changing where they are in their list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sort {
+    /// The order they were put in
+    #[default]
+    Manual,
+    Created,
+    Updated,
+    Completed,
+    Due,
+}
+
+impl Sort {
+    pub const ALL: [Sort; 5] = [
+        Sort::Manual,
+        Sort::Created,
+        Sort::Updated,
+        Sort::Completed,
+        Sort::Due,
+    ];
+
+    /// The newest first, apart from due dates where the soonest come first. Items without the
+    /// time go last, staying in the order they were put in
+    pub fn compare(self, a: &TodoItem, b: &TodoItem) -> Ordering {
+        match self {
+            Sort::Manual => Ordering::Equal,
+            Sort::Created => last_if_none(a.created_at.map(Reverse), b.created_at.map(Reverse)),
+            Sort::Updated => last_if_none(a.updated_at.map(Reverse), b.updated_at.map(Reverse)),
+            Sort::Completed => last_if_none(
+                a.completed_at.filter(|_| a.completed).map(Reverse),
+                b.completed_at.filter(|_| b.completed).map(Reverse),
+            ),
+            Sort::Due => last_if_none(a.due(), b.due()),
+        }
+    }
+
+    /// The items in this order, along with their index in the list
+    pub fn sorted(self, items: &[TodoItem]) -> Vec<(usize, &TodoItem)> {
+        let mut sorted: Vec<_> = items.iter().enumerate().collect();
+
+        // stable, so that equal items keep the order they were put in
+        sorted.sort_by(|(_, a), (_, b)| self.compare(a, b));
+
+        sorted
+    }
+}
+
+/// Compares the values, with `None` after any value
+fn last_if_none<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Sort::Manual => "Manual",
+            Sort::Created => "Created",
+            Sort::Updated => "Updated",
+            Sort::Completed => "Completed",
+            Sort::Due => "Due",
+        })
+    }
+}
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(sort: &str) -> Result<Self, Self::Err> {
+        Sort::ALL
+            .into_iter()
+            .find(|known| known.to_string().eq_ignore_ascii_case(sort))
+            .ok_or_else(|| format!("unknown sort \"{sort}\""))
+    }
+}
@@ -0,0 +1,331 @@
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+pub const TIME_FORMAT: &str = "%H:%M";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TodoItem {
+    pub completed: bool,
+    pub name: String,
+
+    /// Stays the same for the life of the item, so other tools can tell it apart from the others
+    #[serde(default = "Uuid::new_v4")]
+    pub uid: Uuid,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<NaiveDate>,
+
+    /// Only meaningful alongside a `due_date`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_time: Option<NaiveTime>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<TodoItem>,
+
+    /// From `A`, the highest, to `Z`, as in todo.txt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<char>,
+
+    /// Unknown for items saved before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// The last time the item or one of its subtasks was changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+
+    /// Only set while the item is completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl Default for TodoItem {
+    fn default() -> Self {
+        let now = Utc::now();
+
+        Self {
+            completed: false,
+            name: "TodoItem".to_owned(),
+            uid: Uuid::new_v4(),
+            due_date: None,
+            due_time: None,
+            subtasks: Vec::new(),
+            priority: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+            completed_at: None,
+        }
+    }
+}
+
+impl TodoItem {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Records that the item has just been changed
+    pub fn touch(&mut self) {
+        self.updated_at = Some(Utc::now());
+    }
+
+    /// Sets the item, along with every one of its subtasks
+    pub fn set_completed(&mut self, completed: bool) {
+        self.mark_completed(completed);
+
+        for subtask in &mut self.subtasks {
+            subtask.set_completed(completed);
+        }
+    }
+
+    /// Completes the item once every subtask is, and uncompletes it otherwise
+    pub fn complete_from_subtasks(&mut self) {
+        if !self.subtasks.is_empty() {
+            self.mark_completed(self.subtasks.iter().all(|subtask| subtask.completed));
+        }
+    }
+
+    /// Sets only this item, keeping when it was completed if it already was
+    fn mark_completed(&mut self, completed: bool) {
+        if completed == self.completed {
+            return;
+        }
+
+        self.completed = completed;
+        self.completed_at = completed.then(Utc::now);
+        self.touch();
+    }
+
+    pub fn add_subtask(&mut self, name: &str) {
+        self.subtasks.push(TodoItem::new(name));
+        self.complete_from_subtasks();
+        self.touch();
+    }
+
+    /// How many subtasks are completed, out of how many, if there are any
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        let completed = self.subtasks.iter().filter(|subtask| subtask.completed);
+
+        (!self.subtasks.is_empty()).then(|| (completed.count(), self.subtasks.len()))
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_completed(!self.completed);
+    }
+
+    pub fn rename(&mut self, name: &str) {
+        self.name = name.to_string();
+        self.touch();
+    }
+
+    pub fn set_due(&mut self, date: Option<NaiveDate>, time: Option<NaiveTime>) {
+        self.due_date = date;
+        self.due_time = date.and(time);
+        self.touch();
+    }
+
+    /// When the item is due, items without a time are due at the end of the day
+    pub fn due(&self) -> Option<NaiveDateTime> {
+        let date = self.due_date?;
+
+        Some(match self.due_time {
+            Some(time) => date.and_time(time),
+            None => date.succ_opt().unwrap_or(date).and_time(NaiveTime::MIN),
+        })
+    }
+
+    /// The due date as `YYYY-MM-DD`, followed by ` HH:MM` if it has a time
+    pub fn due_text(&self) -> Option<String> {
+        let date = self.due_date?;
+
+        Some(match self.due_time {
+            Some(time) => format!("{} {}", date.format(DATE_FORMAT), time.format(TIME_FORMAT)),
+            None => date.format(DATE_FORMAT).to_string(),
+        })
+    }
+
+    /// Reads a due date written like [`TodoItem::due_text`] does
+    pub fn parse_due_text(text: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+        let (date, time) = match text.trim().split_once(' ') {
+            Some((date, time)) => (date, Some(time.trim())),
+            None => (text.trim(), None),
+        };
+
+        Some((
+            NaiveDate::parse_from_str(date, DATE_FORMAT).ok()?,
+            match time {
+                Some(time) => Some(NaiveTime::parse_from_str(time, TIME_FORMAT).ok()?),
+                None => None,
+            },
+        ))
+    }
+
+    pub fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        !self.completed && self.due().is_some_and(|due| due <= now)
+    }
+
+    pub fn is_due_on(&self, date: NaiveDate) -> bool {
+        self.due_date == Some(date)
+    }
+
+    /// Whether the item is due after `today`, but at most `days` days after it
+    pub fn is_due_within(&self, today: NaiveDate, days: u64) -> bool {
+        let last = today
+            .checked_add_days(Days::new(days))
+            .unwrap_or(NaiveDate::MAX);
+
+        self.due_date
+            .is_some_and(|date| today < date && date <= last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn with_subtasks(names: &[&str]) -> TodoItem {
+        let mut item = TodoItem::new("item");
+
+        for name in names {
+            item.add_subtask(name);
+        }
+
+        item
+    }
+
+    #[test]
+    fn toggle_and_rename() {
+        let mut item = TodoItem::new("milk");
+
+        item.toggle();
+        assert!(item.completed);
+        assert!(item.completed_at.is_some());
+
+        item.toggle();
+        assert!(!item.completed);
+        assert_eq!(item.completed_at, None);
+
+        item.rename("oat milk");
+        assert_eq!(item.name, "oat milk");
+    }
+
+    #[test]
+    fn completing_an_item_completes_its_subtasks() {
+        let mut item = with_subtasks(&["a", "b"]);
+
+        item.set_completed(true);
+        assert!(item.subtasks.iter().all(|subtask| subtask.completed));
+        assert_eq!(item.progress(), Some((2, 2)));
+
+        item.set_completed(false);
+        assert!(item.subtasks.iter().all(|subtask| !subtask.completed));
+    }
+
+    #[test]
+    fn completing_every_subtask_completes_the_item() {
+        let mut item = with_subtasks(&["a", "b"]);
+
+        item.subtasks[0].set_completed(true);
+        item.complete_from_subtasks();
+        assert!(!item.completed);
+        assert_eq!(item.progress(), Some((1, 2)));
+
+        item.subtasks[1].set_completed(true);
+        item.complete_from_subtasks();
+        assert!(item.completed);
+
+        // a new subtask has yet to be done
+        item.add_subtask("c");
+        assert!(!item.completed);
+    }
+
+    #[test]
+    fn items_without_subtasks_keep_their_state() {
+        let mut item = TodoItem::new("item");
+
+        item.set_completed(true);
+        item.complete_from_subtasks();
+        assert!(item.completed);
+        assert_eq!(item.progress(), None);
+    }
+
+    #[test]
+    fn due_time_needs_a_date() {
+        let mut item = TodoItem::new("item");
+
+        item.set_due(None, Some(time(9, 0)));
+        assert_eq!(item.due_time, None);
+        assert_eq!(item.due(), None);
+
+        item.set_due(Some(date(2024, 5, 1)), Some(time(9, 0)));
+        assert_eq!(item.due_text().as_deref(), Some("2024-05-01 09:00"));
+        assert_eq!(
+            TodoItem::parse_due_text("2024-05-01 09:00"),
+            Some((date(2024, 5, 1), Some(time(9, 0))))
+        );
+    }
+
+    #[test]
+    fn overdue_at_the_due_time() {
+        let mut item = TodoItem::new("item");
+        item.set_due(Some(date(2024, 5, 1)), Some(time(9, 0)));
+
+        assert!(!item.is_overdue(date(2024, 5, 1).and_time(time(8, 59))));
+        assert!(item.is_overdue(date(2024, 5, 1).and_time(time(9, 0))));
+
+        item.set_completed(true);
+        assert!(!item.is_overdue(date(2024, 5, 2).and_time(time(0, 0))));
+    }
+
+    #[test]
+    fn overdue_after_the_due_day_without_a_time() {
+        let mut item = TodoItem::new("item");
+        item.set_due(Some(date(2024, 5, 1)), None);
+
+        assert!(!item.is_overdue(date(2024, 5, 1).and_time(time(23, 59))));
+        assert!(item.is_overdue(date(2024, 5, 2).and_time(NaiveTime::MIN)));
+    }
+
+    #[test]
+    fn due_on_the_day_only() {
+        let mut item = TodoItem::new("item");
+        item.set_due(Some(date(2024, 5, 1)), Some(time(23, 0)));
+
+        assert!(item.is_due_on(date(2024, 5, 1)));
+        assert!(!item.is_due_on(date(2024, 4, 30)));
+        assert!(!item.is_due_on(date(2024, 5, 2)));
+    }
+
+    #[test]
+    fn due_within_excludes_today_and_includes_the_last_day() {
+        let mut item = TodoItem::new("item");
+        let today = date(2024, 5, 1);
+
+        item.set_due(Some(today), None);
+        assert!(!item.is_due_within(today, 7));
+
+        item.set_due(Some(date(2024, 5, 2)), None);
+        assert!(item.is_due_within(today, 7));
+
+        item.set_due(Some(date(2024, 5, 8)), None);
+        assert!(item.is_due_within(today, 7));
+
+        item.set_due(Some(date(2024, 5, 9)), None);
+        assert!(!item.is_due_within(today, 7));
+
+        item.set_due(None, None);
+        assert!(!item.is_due_within(today, 7));
+    }
+}
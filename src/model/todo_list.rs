@@ -0,0 +1,484 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{search::Query, todo_item::TodoItem};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TodoList {
+    pub todo_items: Vec<TodoItem>,
+    pub name: String,
+
+    /// Stays the same for the life of the list, whatever happens to the lists around it
+    #[serde(default = "Uuid::new_v4")]
+    pub uid: Uuid,
+
+    /// Unknown for lists saved before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+
+    /// The last time the list or one of its items was changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Where an item is in its list, subtasks being found through the item they belong to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemPath {
+    pub index: usize,
+    pub subtask: Option<usize>,
+}
+
+impl ItemPath {
+    pub fn subtask(index: usize, subtask: usize) -> Self {
+        Self {
+            index,
+            subtask: Some(subtask),
+        }
+    }
+}
+
+impl From<usize> for ItemPath {
+    fn from(index: usize) -> Self {
+        Self {
+            index,
+            subtask: None,
+        }
+    }
+}
+
+/// The number the item is shown with, counting from 1, subtasks having the number of their item
+/// before theirs like `2.1`
+impl fmt::Display for ItemPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.index + 1)?;
+
+        match self.subtask {
+            Some(subtask) => write!(f, ".{}", subtask + 1),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for ItemPath {
+    type Err = String;
+
+    fn from_str(number: &str) -> Result<Self, Self::Err> {
+        let index = |part: &str| part.parse::<usize>().ok()?.checked_sub(1);
+        let path = match number.split_once('.') {
+            Some((item, subtask)) => index(item)
+                .zip(index(subtask))
+                .map(|(item, subtask)| ItemPath::subtask(item, subtask)),
+            None => index(number).map(ItemPath::from),
+        };
+
+        path.ok_or_else(|| format!("invalid item number \"{number}\""))
+    }
+}
+
+impl Default for TodoList {
+    fn default() -> Self {
+        let now = Utc::now();
+
+        Self {
+            todo_items: Vec::new(),
+            name: "TodoList".to_owned(),
+            uid: Uuid::new_v4(),
+            created_at: Some(now),
+            updated_at: Some(now),
+        }
+    }
+}
+
+impl TodoList {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Records that the list has just been changed
+    pub fn touch(&mut self) {
+        self.updated_at = Some(Utc::now());
+    }
+
+    /// Records that the list and the item at the path have just been changed, a subtask changing
+    /// its item too
+    fn touch_item(&mut self, path: ItemPath) {
+        if let Some(item) = self.todo_items.get_mut(path.index) {
+            item.touch();
+        }
+
+        self.touch();
+    }
+
+    pub fn rename(&mut self, name: &str) {
+        self.name = name.to_string();
+        self.touch();
+    }
+
+    pub fn add(&mut self, name: &str) {
+        self.todo_items.push(TodoItem::new(name));
+        self.touch();
+    }
+
+    pub fn add_subtask(&mut self, index: usize, name: &str) -> Option<()> {
+        self.todo_items.get_mut(index)?.add_subtask(name);
+        self.touch();
+
+        Some(())
+    }
+
+    pub fn get(&self, path: impl Into<ItemPath>) -> Option<&TodoItem> {
+        let path = path.into();
+        let item = self.todo_items.get(path.index)?;
+
+        match path.subtask {
+            Some(subtask) => item.subtasks.get(subtask),
+            None => Some(item),
+        }
+    }
+
+    pub fn get_mut(&mut self, path: impl Into<ItemPath>) -> Option<&mut TodoItem> {
+        let path = path.into();
+        let item = self.todo_items.get_mut(path.index)?;
+
+        match path.subtask {
+            Some(subtask) => item.subtasks.get_mut(subtask),
+            None => Some(item),
+        }
+    }
+
+    /// Where the item with the uid is, among the items and their subtasks
+    pub fn find(&self, uid: Uuid) -> Option<ItemPath> {
+        self.todo_items
+            .iter()
+            .enumerate()
+            .find_map(|(index, item)| {
+                if item.uid == uid {
+                    return Some(index.into());
+                }
+
+                let subtask = item
+                    .subtasks
+                    .iter()
+                    .position(|subtask| subtask.uid == uid)?;
+
+                Some(ItemPath::subtask(index, subtask))
+            })
+    }
+
+    /// Inserts the item at the index, or at the end if the index is past it
+    pub fn insert(&mut self, index: usize, item: TodoItem) {
+        self.todo_items
+            .insert(index.min(self.todo_items.len()), item);
+        self.touch();
+    }
+
+    /// Adds the items at the end
+    pub fn extend(&mut self, items: impl IntoIterator<Item = TodoItem>) {
+        self.todo_items.extend(items);
+        self.touch();
+    }
+
+    pub fn remove(&mut self, path: impl Into<ItemPath>) -> Option<TodoItem> {
+        let path = path.into();
+
+        let removed = match path.subtask {
+            Some(subtask) => {
+                let item = self.todo_items.get_mut(path.index)?;
+                let removed =
+                    (subtask < item.subtasks.len()).then(|| item.subtasks.remove(subtask))?;
+
+                item.complete_from_subtasks();
+                item.touch();
+
+                removed
+            }
+            None => {
+                (path.index < self.todo_items.len()).then(|| self.todo_items.remove(path.index))?
+            }
+        };
+
+        self.touch();
+
+        Some(removed)
+    }
+
+    /// Completing an item completes its subtasks, and completing every subtask completes the item
+    pub fn set_completed(&mut self, path: impl Into<ItemPath>, completed: bool) -> Option<()> {
+        let path = path.into();
+
+        self.get_mut(path)?.set_completed(completed);
+
+        if path.subtask.is_some() {
+            self.todo_items[path.index].complete_from_subtasks();
+        }
+
+        self.touch_item(path);
+
+        Some(())
+    }
+
+    pub fn toggle(&mut self, path: impl Into<ItemPath>) -> Option<()> {
+        let path = path.into();
+        let completed = self.get(path)?.completed;
+
+        self.set_completed(path, !completed)
+    }
+
+    pub fn rename_item(&mut self, path: impl Into<ItemPath>, name: &str) -> Option<()> {
+        let path = path.into();
+
+        self.get_mut(path)?.rename(name);
+        self.touch_item(path);
+
+        Some(())
+    }
+
+    pub fn set_due(
+        &mut self,
+        path: impl Into<ItemPath>,
+        date: Option<NaiveDate>,
+        time: Option<NaiveTime>,
+    ) -> Option<()> {
+        let path = path.into();
+
+        self.get_mut(path)?.set_due(date, time);
+        self.touch_item(path);
+
+        Some(())
+    }
+
+    /// Moves the item at `from` so that it ends up at `to`, shifting the items in between
+    pub fn move_item(&mut self, from: usize, to: usize) -> Option<()> {
+        if from >= self.todo_items.len() || to >= self.todo_items.len() {
+            return None;
+        }
+
+        let item = self.todo_items.remove(from);
+        self.todo_items.insert(to, item);
+        self.touch();
+
+        Some(())
+    }
+
+    /// The items and subtasks whose name matches the query, along with where they are
+    pub fn search<'a>(
+        &'a self,
+        query: &'a Query,
+    ) -> impl Iterator<Item = (ItemPath, &'a TodoItem)> {
+        self.todo_items
+            .iter()
+            .enumerate()
+            .flat_map(|(index, item)| {
+                let subtasks = item
+                    .subtasks
+                    .iter()
+                    .enumerate()
+                    .map(move |(subtask, item)| (ItemPath::subtask(index, subtask), item));
+
+                std::iter::once((ItemPath::from(index), item)).chain(subtasks)
+            })
+            .filter(|(_, item)| query.matches(&item.name))
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.todo_items.iter().filter(|item| item.completed).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(names: &[&str]) -> TodoList {
+        let mut list = TodoList::new("list");
+
+        for name in names {
+            list.add(name);
+        }
+
+        list
+    }
+
+    fn names(list: &TodoList) -> Vec<&str> {
+        list.todo_items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn add_and_remove() {
+        let mut list = list(&["a", "b", "c"]);
+
+        assert_eq!(names(&list), ["a", "b", "c"]);
+        assert_eq!(list.remove(1).map(|item| item.name), Some("b".to_owned()));
+        assert_eq!(names(&list), ["a", "c"]);
+        assert_eq!(list.remove(2), None);
+    }
+
+    #[test]
+    fn extending_touches_the_list() {
+        let mut list = list(&["a"]);
+        list.updated_at = None;
+
+        list.extend([TodoItem::new("b"), TodoItem::new("c")]);
+        assert_eq!(names(&list), ["a", "b", "c"]);
+        assert!(list.updated_at.is_some());
+    }
+
+    #[test]
+    fn toggle_and_rename() {
+        let mut list = list(&["a", "b"]);
+
+        list.toggle(1);
+        assert_eq!(list.completed_count(), 1);
+        assert!(list.todo_items[1].completed);
+
+        list.toggle(1);
+        assert_eq!(list.completed_count(), 0);
+
+        list.rename_item(0, "z");
+        assert_eq!(names(&list), ["z", "b"]);
+        assert_eq!(list.toggle(5), None);
+        assert_eq!(list.rename_item(5, "y"), None);
+    }
+
+    #[test]
+    fn move_item_shifts_the_ones_in_between() {
+        let mut list = list(&["a", "b", "c", "d"]);
+
+        list.move_item(0, 2);
+        assert_eq!(names(&list), ["b", "c", "a", "d"]);
+
+        list.move_item(3, 0);
+        assert_eq!(names(&list), ["d", "b", "c", "a"]);
+
+        assert_eq!(list.move_item(0, 4), None);
+        assert_eq!(names(&list), ["d", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn insert_past_the_end_appends() {
+        let mut list = list(&["a"]);
+
+        list.insert(10, TodoItem::new("b"));
+        list.insert(0, TodoItem::new("c"));
+        assert_eq!(names(&list), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn search_finds_subtasks() {
+        let mut list = list(&["buy milk", "garden"]);
+        list.add_subtask(1, "buy seeds");
+        list.add_subtask(1, "dig");
+
+        let query = Query::new("buy");
+        let found: Vec<_> = list
+            .search(&query)
+            .map(|(path, item)| (path, item.name.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            [
+                (ItemPath::from(0), "buy milk"),
+                (ItemPath::subtask(1, 0), "buy seeds")
+            ]
+        );
+    }
+
+    #[test]
+    fn item_numbers() {
+        assert_eq!("2".parse(), Ok(ItemPath::from(1)));
+        assert_eq!("2.1".parse(), Ok(ItemPath::subtask(1, 0)));
+        assert!("0".parse::<ItemPath>().is_err());
+        assert!("2.".parse::<ItemPath>().is_err());
+        assert!("2.1.1".parse::<ItemPath>().is_err());
+        assert_eq!(ItemPath::subtask(1, 0).to_string(), "2.1");
+        assert_eq!(ItemPath::from(0).to_string(), "1");
+    }
+
+    #[test]
+    fn subtask_paths() {
+        let mut list = list(&["a", "b"]);
+        list.add_subtask(1, "b1");
+        list.add_subtask(1, "b2");
+
+        let b2 = list.todo_items[1].subtasks[1].uid;
+        assert_eq!(list.find(b2), Some(ItemPath::subtask(1, 1)));
+        assert_eq!(list.find(list.todo_items[0].uid), Some(0.into()));
+        assert_eq!(list.find(Uuid::new_v4()), None);
+
+        assert_eq!(
+            list.get(ItemPath::subtask(1, 0))
+                .map(|item| item.name.as_str()),
+            Some("b1")
+        );
+        assert_eq!(list.get(ItemPath::subtask(0, 0)), None);
+        assert_eq!(list.add_subtask(5, "x"), None);
+
+        list.rename_item(ItemPath::subtask(1, 0), "c1");
+        assert_eq!(list.todo_items[1].subtasks[0].name, "c1");
+
+        let removed = list.remove(ItemPath::subtask(1, 0));
+        assert_eq!(removed.map(|item| item.name), Some("c1".to_owned()));
+        assert_eq!(list.find(b2), Some(ItemPath::subtask(1, 0)));
+        assert_eq!(list.remove(ItemPath::subtask(1, 5)), None);
+    }
+
+    #[test]
+    fn completing_subtasks_completes_their_item() {
+        let mut list = list(&["a"]);
+        list.add_subtask(0, "a1");
+        list.add_subtask(0, "a2");
+
+        list.set_completed(ItemPath::subtask(0, 0), true);
+        assert!(!list.todo_items[0].completed);
+
+        list.set_completed(ItemPath::subtask(0, 1), true);
+        assert!(list.todo_items[0].completed);
+
+        list.toggle(ItemPath::subtask(0, 1));
+        assert!(!list.todo_items[0].completed);
+
+        // removing the last one left to do completes the item
+        list.remove(ItemPath::subtask(0, 1));
+        assert!(list.todo_items[0].completed);
+    }
+
+    #[test]
+    fn completing_an_item_completes_its_subtasks() {
+        let mut list = list(&["a"]);
+        list.add_subtask(0, "a1");
+        list.add_subtask(0, "a2");
+
+        list.set_completed(0, true);
+        assert!(list.todo_items[0]
+            .subtasks
+            .iter()
+            .all(|item| item.completed));
+
+        list.set_completed(0, false);
+        assert!(list.todo_items[0]
+            .subtasks
+            .iter()
+            .all(|item| !item.completed));
+    }
+
+    #[test]
+    fn changes_touch_the_list() {
+        let mut list = list(&["a"]);
+        list.updated_at = None;
+        list.todo_items[0].updated_at = None;
+
+        list.rename_item(0, "b");
+        assert!(list.updated_at.is_some());
+        assert!(list.todo_items[0].updated_at.is_some());
+    }
+}
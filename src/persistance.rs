@@ -0,0 +1,485 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    env,
+    error::Error,
+    fmt, fs as std_fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use async_std::{fs, io::WriteExt};
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{
+    formats::Format,
+    model::TodoList,
+    recovery,
+    save_file::{MigrationError, SaveFile, CURRENT_VERSION},
+    settings::Settings,
+};
+
+/// How many backups are kept, the oldest ones are removed past it
+const BACKUP_COUNT: usize = 10;
+/// How long after a backup the next one is made, so that every save does not push out the older ones
+const BACKUP_INTERVAL: TimeDelta = TimeDelta::minutes(10);
+/// How the time is written in the names of backups
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[allow(async_fn_in_trait)]
+pub trait Persistance {
+    /// Saves by writing to a temporary file which then replaces the save file, so a failed write
+    /// never leaves it half written
+    async fn save<T: Serialize>(items: T) -> Result<Saved, PersistError> {
+        let save_string = serde_json::to_string(&items)
+            .map_err(|error| PersistError::Save(SaveError::Compose(Arc::new(error))))?;
+
+        Self::save_string(&save_string).await
+    }
+
+    /// Saves the lists in the format of the save file, which is todo.txt for `.txt` files
+    async fn save_lists(lists: Vec<TodoList>) -> Result<Saved, PersistError> {
+        let path = Self::config_path()?;
+
+        if is_todo_txt(&path) {
+            return Self::save_string(&Format::TodoTxt.export(&lists)).await;
+        }
+
+        match loaded_version(&path) {
+            // what a newer version saved would be lost by writing it in this version's format
+            Some(Some(version)) if version > CURRENT_VERSION => {
+                return Err(PersistError::Save(SaveError::TooNew(path, version)));
+            }
+            // some other file the save file was pointed at
+            Some(None) if path.exists() => {
+                return Err(PersistError::Save(SaveError::Unrecognized(path)));
+            }
+            _ => {}
+        }
+
+        Self::save(SaveFile::new(lists)).await
+    }
+
+    /// Writes the save file, then backs it up, a failed backup leaving the save as it is
+    async fn save_string(save_string: &str) -> Result<Saved, PersistError> {
+        let path = Self::config_path()?;
+        write_atomic(&path, save_string)
+            .await
+            .map_err(|error| PersistError::Save(SaveError::Write(path, Arc::new(error))))?;
+
+        Ok(Saved {
+            backup_error: Self::back_up(save_string).await.err(),
+        })
+    }
+
+    /// Keeps a copy of what was saved, if the latest backup is old enough
+    async fn back_up(save_string: &str) -> Result<(), PersistError> {
+        let now = Local::now().naive_local();
+        let backups = Self::backups().map_err(|error| match error {
+            PersistError::Load(LoadError::Read(path, error)) => {
+                PersistError::Save(SaveError::Backup(path, error))
+            }
+            error => error,
+        })?;
+
+        if backups
+            .first()
+            .is_some_and(|latest| now - latest.time < BACKUP_INTERVAL)
+        {
+            return Ok(());
+        }
+
+        let backup_error = |path: &Path, error| {
+            PersistError::Save(SaveError::Backup(path.into(), Arc::new(error)))
+        };
+
+        let path = Self::backups_dir()?;
+        fs::create_dir_all(&path)
+            .await
+            .map_err(|error| backup_error(&path, error))?;
+
+        // backups are in the same format as the save file
+        let extension = Self::config_path()?
+            .extension()
+            .map(|extension| extension.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "json".to_owned());
+        let path = path.join(format!("{}.{extension}", now.format(BACKUP_TIME_FORMAT)));
+        write_atomic(&path, save_string)
+            .await
+            .map_err(|error| backup_error(&path, error))?;
+
+        // the new backup is not in `backups`, so one less of those is kept
+        for backup in backups.iter().skip(BACKUP_COUNT - 1) {
+            fs::remove_file(&backup.path)
+                .await
+                .map_err(|error| backup_error(&backup.path, error))?;
+        }
+
+        Ok(())
+    }
+
+    fn load<T: DeserializeOwned>() -> Result<T, PersistError> {
+        load_from(&Self::config_path()?)
+    }
+
+    /// Loads the lists in the format of the save file, like [`Persistance::save_lists`] saves them
+    fn load_lists() -> Result<Vec<TodoList>, PersistError> {
+        let path = Self::config_path()?;
+
+        if is_todo_txt(&path) {
+            return load_lists_from(&path);
+        }
+
+        // files from older versions are only written in the current one by the next save, the
+        // uids made up for them being the same until then
+        load_save_file(&path).map(|save_file| save_file.lists)
+    }
+
+    async fn _load_async<T: DeserializeOwned>() -> Result<T, PersistError> {
+        let path = Self::config_path()?;
+        let load_bytes = fs::read(&path)
+            .await
+            .map_err(|error| PersistError::Load(LoadError::Read(path.clone(), Arc::new(error))))?;
+
+        let loaded: T = serde_json::from_slice(&load_bytes)
+            .map_err(|error| PersistError::Load(LoadError::Parse(path, Arc::new(error))))?;
+
+        Ok(loaded)
+    }
+
+    fn config_path() -> Result<PathBuf, PersistError>;
+
+    /// Where backups of the save file go, next to it
+    fn backups_dir() -> Result<PathBuf, PersistError> {
+        let path = Self::config_path()?;
+        let stem = path
+            .file_stem()
+            .ok_or(PersistError::Path)?
+            .to_string_lossy();
+
+        Ok(path.with_file_name(format!("{stem}_backups")))
+    }
+
+    /// Moves the save file aside with the time in its name, so that it is not saved over
+    fn quarantine() -> Result<PathBuf, PersistError> {
+        let path = Self::config_path()?;
+        let stem = path
+            .file_stem()
+            .ok_or(PersistError::Path)?
+            .to_string_lossy();
+        let quarantined = path.with_file_name(format!(
+            "{stem}.corrupt-{}.json",
+            Local::now().format(BACKUP_TIME_FORMAT)
+        ));
+
+        std_fs::rename(&path, &quarantined)
+            .map_err(|error| PersistError::Load(LoadError::Quarantine(path, Arc::new(error))))?;
+
+        Ok(quarantined)
+    }
+
+    /// Every backup, the latest first
+    fn backups() -> Result<Vec<Backup>, PersistError> {
+        let path = Self::backups_dir()?;
+
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups: Vec<Backup> = std_fs::read_dir(&path)
+            .map_err(|error| PersistError::Load(LoadError::Read(path, Arc::new(error))))?
+            .filter_map(|entry| Backup::from_path(entry.ok()?.path()))
+            .collect();
+
+        backups.sort_by_key(|backup| Reverse(backup.time));
+
+        Ok(backups)
+    }
+}
+
+/// A save that went through, even if it could not be backed up
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct Saved {
+    /// Why the backup was not made, which is only worth a warning as the save file was written
+    pub backup_error: Option<PersistError>,
+}
+
+/// A copy of the save file from an earlier time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub time: NaiveDateTime,
+}
+
+impl Backup {
+    /// Reads the time from the name of the file, anything else in the folder is not a backup
+    fn from_path(path: PathBuf) -> Option<Self> {
+        let time =
+            NaiveDateTime::parse_from_str(path.file_stem()?.to_str()?, BACKUP_TIME_FORMAT).ok()?;
+
+        Some(Self { path, time })
+    }
+
+    pub fn load<T: DeserializeOwned>(&self) -> Result<T, PersistError> {
+        load_from(&self.path)
+    }
+
+    pub fn load_lists(&self) -> Result<Vec<TodoList>, PersistError> {
+        load_lists_from(&self.path)
+    }
+}
+
+/// Whether the lists in the file are kept as todo.txt, so that other todo.txt tools can share it
+pub fn is_todo_txt(path: &Path) -> bool {
+    Format::from_path(path) == Some(Format::TodoTxt)
+}
+
+fn load_lists_from(path: &Path) -> Result<Vec<TodoList>, PersistError> {
+    if !is_todo_txt(path) {
+        return load_save_file(path).map(|save_file| save_file.lists);
+    }
+
+    let text = std_fs::read_to_string(path)
+        .map_err(|error| PersistError::Load(LoadError::Read(path.into(), Arc::new(error))))?;
+
+    Ok(Format::TodoTxt.import(&text))
+}
+
+fn load_from<T: DeserializeOwned>(path: &Path) -> Result<T, PersistError> {
+    let load_bytes = std_fs::read(path)
+        .map_err(|error| PersistError::Load(LoadError::Read(path.into(), Arc::new(error))))?;
+
+    let loaded: T = serde_json::from_slice(&load_bytes)
+        .map_err(|error| PersistError::Load(LoadError::Parse(path.into(), Arc::new(error))))?;
+
+    Ok(loaded)
+}
+
+/// Reads the save file, telling a file saved by a newer version and a file which is not a save
+/// file apart from a broken one
+fn load_save_file(path: &Path) -> Result<SaveFile, PersistError> {
+    let bytes = std_fs::read(path)
+        .map_err(|error| PersistError::Load(LoadError::Read(path.into(), Arc::new(error))))?;
+    let unrecognized = |error| {
+        record_version(path, None);
+
+        PersistError::Load(LoadError::Unrecognized(path.into(), error))
+    };
+
+    let value: Value = match serde_json::from_slice(&bytes) {
+        Ok(value) => value,
+        // only a broken save file is worth recovering, and moving aside
+        Err(error) if recovery::looks_like_save_file(&String::from_utf8_lossy(&bytes)) => {
+            return Err(PersistError::Load(LoadError::Parse(
+                path.into(),
+                Arc::new(error),
+            )))
+        }
+        Err(_) => return Err(unrecognized(MigrationError::Unrecognized)),
+    };
+
+    let save_file = SaveFile::try_from(value).map_err(|error| match error {
+        MigrationError::TooNew(version) => {
+            record_version(path, Some(version));
+
+            PersistError::Load(LoadError::TooNew(path.into(), version))
+        }
+        error => unrecognized(error),
+    })?;
+
+    record_version(path, Some(save_file.version));
+
+    Ok(save_file)
+}
+
+/// The version of each save file when it was last loaded, `None` for files which are not save
+/// files, so that saving knows whether it can write over the file without reading it again
+static LOADED_VERSIONS: RwLock<BTreeMap<PathBuf, Option<u32>>> = RwLock::new(BTreeMap::new());
+
+fn record_version(path: &Path, version: Option<u32>) {
+    LOADED_VERSIONS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(path.into(), version);
+}
+
+/// The version the file at the path had when it was last loaded, `Some(None)` if it was not a save
+/// file
+fn loaded_version(path: &Path) -> Option<Option<u32>> {
+    LOADED_VERSIONS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(path)
+        .copied()
+}
+
+/// Writes to a temporary file next to the path, then moves it over the path
+async fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_owned();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = fs::File::create(&temp_path).await?;
+    file.write_all(contents.as_bytes()).await?;
+    file.sync_all().await?;
+    drop(file);
+
+    fs::rename(&temp_path, path).await
+}
+
+/// The save file chosen with `--file` or opened from the gui, taking over from everything else
+static PATH_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// The environment variable holding the save file to use
+pub const PATH_ENV: &str = "ICED_TODO_FILE";
+
+/// The save file shared by the gui and the cli, which is the first of the one set with
+/// [`set_save_path`], the one in [`PATH_ENV`], the one in the settings and the default one
+pub fn save_path() -> Result<PathBuf, PersistError> {
+    if let Some(path) = PATH_OVERRIDE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+    {
+        return Ok(path);
+    }
+
+    if let Some(path) = env::var_os(PATH_ENV).filter(|path| !path.is_empty()) {
+        return Ok(path.into());
+    }
+
+    match Settings::load().file {
+        Some(path) => Ok(path),
+        None => default_path(),
+    }
+}
+
+/// Makes every later load and save use the path
+pub fn set_save_path(path: PathBuf) {
+    *PATH_OVERRIDE
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(path);
+}
+
+/// The save file used when no other has been chosen
+pub fn default_path() -> Result<PathBuf, PersistError> {
+    let mut path_buf = dirs::config_dir().ok_or(PersistError::Path)?;
+    path_buf.push("todo_save.json");
+
+    Ok(path_buf)
+}
+
+#[derive(Debug, Clone)]
+pub enum PersistError {
+    Save(SaveError),
+    Load(LoadError),
+    Path,
+}
+
+/// Errors along with the file they happened to and what caused them
+#[derive(Debug, Clone)]
+pub enum SaveError {
+    Write(PathBuf, Arc<io::Error>),
+    Compose(Arc<serde_json::Error>),
+    Backup(PathBuf, Arc<io::Error>),
+    /// The file was saved by a newer version, with the version it was saved with
+    TooNew(PathBuf, u32),
+    /// The file is not a save file
+    Unrecognized(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub enum LoadError {
+    Read(PathBuf, Arc<io::Error>),
+    Parse(PathBuf, Arc<serde_json::Error>),
+    Quarantine(PathBuf, Arc<io::Error>),
+    /// The file was saved by a newer version, with the version it was saved with
+    TooNew(PathBuf, u32),
+    /// The file is not a save file, rather than a broken one
+    Unrecognized(PathBuf, MigrationError),
+}
+
+impl PersistError {
+    /// What went wrong in a few words, without the details
+    pub fn summary(&self) -> &'static str {
+        match self {
+            PersistError::Save(save_error) => match save_error {
+                SaveError::Write(..) => "Failed to write to save file",
+                SaveError::Compose(_) => "Failed to compose json data",
+                SaveError::Backup(..) => "Saved, but failed to back up the save file",
+                SaveError::TooNew(..) => "Not saving over a file from a newer version",
+                SaveError::Unrecognized(_) => "Not saving over a file which is not a save file",
+            },
+            PersistError::Load(load_error) => match load_error {
+                LoadError::Read(..) => "Failed to read config file",
+                LoadError::Parse(..) => "Failed to parse config data",
+                LoadError::Quarantine(..) => "Failed to move the unreadable save file aside",
+                LoadError::TooNew(..) => "Saved by a newer version of iced-todo",
+                LoadError::Unrecognized(..) => "Not an iced-todo save file",
+            },
+            PersistError::Path => "Could not get config directory",
+        }
+    }
+
+    /// The file the error happened to
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            PersistError::Save(
+                SaveError::Write(path, _)
+                | SaveError::Backup(path, _)
+                | SaveError::TooNew(path, _)
+                | SaveError::Unrecognized(path),
+            )
+            | PersistError::Load(
+                LoadError::Read(path, _)
+                | LoadError::Parse(path, _)
+                | LoadError::Quarantine(path, _)
+                | LoadError::TooNew(path, _)
+                | LoadError::Unrecognized(path, _),
+            ) => Some(path),
+            PersistError::Save(SaveError::Compose(_)) | PersistError::Path => None,
+        }
+    }
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.summary())?;
+
+        if let Some(path) = self.path() {
+            write!(f, ": {}", path.display())?;
+        }
+
+        match self {
+            PersistError::Load(LoadError::Parse(_, error)) if error.line() > 0 => {
+                write!(f, ", line {}, column {}", error.line(), error.column())
+            }
+            PersistError::Save(SaveError::TooNew(_, version))
+            | PersistError::Load(LoadError::TooNew(_, version)) => write!(
+                f,
+                ", format version {version} where this one reads up to {CURRENT_VERSION}"
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Error for PersistError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PersistError::Save(SaveError::Write(_, error) | SaveError::Backup(_, error))
+            | PersistError::Load(LoadError::Read(_, error) | LoadError::Quarantine(_, error)) => {
+                Some(error.as_ref())
+            }
+            PersistError::Save(SaveError::Compose(error))
+            | PersistError::Load(LoadError::Parse(_, error)) => Some(error.as_ref()),
+            PersistError::Load(LoadError::Unrecognized(_, error)) => Some(error),
+            PersistError::Save(SaveError::TooNew(..) | SaveError::Unrecognized(_))
+            | PersistError::Load(LoadError::TooNew(..))
+            | PersistError::Path => None,
+        }
+    }
+}
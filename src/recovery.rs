@@ -0,0 +1,152 @@
+use std::{fs, path::Path};
+
+use serde_json::Value;
+
+use crate::{
+    model::{TodoItem, TodoList},
+    save_file,
+};
+
+/// What could still be read from a save file that failed to parse
+#[derive(Debug, Default)]
+pub struct Recovered {
+    pub lists: Vec<TodoList>,
+    /// How many lists and items could not be read
+    pub skipped: usize,
+}
+
+/// Reads whatever lists and items are still whole in a broken save file
+pub fn recover(path: &Path) -> Recovered {
+    let mut recovered = Recovered::default();
+
+    let Ok(bytes) = fs::read(path) else {
+        return recovered;
+    };
+    let text = String::from_utf8_lossy(&bytes);
+
+    let Some(Ok((_, Value::Array(lists)))) = serde_json::from_str(&text)
+        .ok()
+        .or_else(|| complete_truncated(&text))
+        .map(save_file::split_version)
+    else {
+        return recovered;
+    };
+
+    for list in lists {
+        if let Some(list) = recover_list(list, &mut recovered.skipped) {
+            recovered.lists.push(list);
+        }
+    }
+
+    recovered
+}
+
+/// Whether text which failed to parse is what is left of a save file, rather than some other file
+/// which was never one, an empty file being a save file that lost everything
+pub fn looks_like_save_file(text: &str) -> bool {
+    text.trim().is_empty()
+        // the version is written first
+        || text.trim_start().starts_with(r#"{"version":"#)
+        || complete_truncated(text)
+            .map(save_file::split_version)
+            .is_some_and(|split| matches!(split, Ok((_, Value::Array(_)))))
+}
+
+/// Keeps the items that can be read when the list as a whole can't
+fn recover_list(value: Value, skipped: &mut usize) -> Option<TodoList> {
+    if let Ok(list) = serde_json::from_value(value.clone()) {
+        return Some(list);
+    }
+
+    let Value::Object(mut fields) = value else {
+        *skipped += 1;
+        return None;
+    };
+
+    // the items come before the name, so a cut off list has lost its name
+    let mut list = TodoList::new(
+        fields
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("Recovered list"),
+    );
+
+    if let Some(Value::Array(items)) = fields.remove("todo_items") {
+        for item in items {
+            match serde_json::from_value::<TodoItem>(item) {
+                Ok(item) => list.todo_items.push(item),
+                Err(_) => *skipped += 1,
+            }
+        }
+    }
+
+    Some(list)
+}
+
+/// Cuts the text after the last object or array that still parses once everything left open
+/// is closed, which is what is left of a file whose end was lost or mangled
+fn complete_truncated(text: &str) -> Option<Value> {
+    let mut open = Vec::new();
+    let mut cuts = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '[' => open.push(']'),
+            '{' => open.push('}'),
+            ']' | '}' => {
+                open.pop();
+                cuts.push((index + 1, open.clone()));
+            }
+            _ => {}
+        }
+    }
+
+    cuts.into_iter().rev().find_map(|(end, open)| {
+        let mut completed = text[..end].to_owned();
+        completed.extend(open.iter().rev());
+
+        serde_json::from_str(&completed).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cut_off_save_files_look_like_save_files() {
+        assert!(looks_like_save_file(""));
+        assert!(looks_like_save_file(
+            r#"{"version":4,"lists":[{"name":"a","to"#
+        ));
+        assert!(looks_like_save_file(
+            r#"[{"name":"a","todo_items":[]},{"na"#
+        ));
+        assert!(looks_like_save_file(
+            r#"{"version":4,"lists":[{"name":"a","todo_items":[{"name":"b"}]}, {"#
+        ));
+    }
+
+    #[test]
+    fn other_files_do_not_look_like_save_files() {
+        assert!(!looks_like_save_file("# Notes\n- [ ] call mom\n"));
+        assert!(!looks_like_save_file("list,name\nHome,garden\n"));
+        assert!(!looks_like_save_file(
+            r#"{"name":"package","dependencies":{"a":"1"},"#
+        ));
+    }
+}
@@ -0,0 +1,313 @@
+//! The layout of the save file, and how files saved by older versions are brought up to date
+
+use std::{error::Error, fmt};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::model::TodoList;
+
+/// The version written by this build
+pub const CURRENT_VERSION: u32 = 4;
+
+/// Upgrades the lists of a file from the version at the index plus one to the next version
+const MIGRATIONS: &[fn(Value) -> Value] = &[
+    // 1 -> 2: the lists moved into an envelope, which `SaveFile` takes care of
+    |lists| lists,
+    // 2 -> 3: items have a uid
+    add_uids,
+    // 3 -> 4: lists have a uid
+    add_list_uids,
+];
+
+/// What is written to the save file, the lists along with the version they were saved with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "Value")]
+pub struct SaveFile {
+    /// The version the file was saved with, the lists having been brought up to date since
+    pub version: u32,
+    pub lists: Vec<TodoList>,
+}
+
+#[derive(Debug, Clone)]
+pub enum MigrationError {
+    /// Saved by a newer version, which this one can't know how to read
+    TooNew(u32),
+    /// Neither a list of lists nor an envelope
+    Unrecognized,
+    /// The lists did not match what their version should look like
+    Invalid(String),
+}
+
+impl SaveFile {
+    pub fn new(lists: Vec<TodoList>) -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            lists,
+        }
+    }
+}
+
+impl TryFrom<Value> for SaveFile {
+    type Error = MigrationError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let (version, lists) = split_version(value)?;
+
+        if version > CURRENT_VERSION {
+            return Err(MigrationError::TooNew(version));
+        }
+
+        let lists = MIGRATIONS
+            .iter()
+            .skip(version.saturating_sub(1) as usize)
+            .fold(lists, |lists, migrate| migrate(lists));
+
+        Ok(Self {
+            version,
+            lists: serde_json::from_value(lists)
+                .map_err(|error| MigrationError::Invalid(error.to_string()))?,
+        })
+    }
+}
+
+/// Separates the lists from the version they were saved with
+pub fn split_version(value: Value) -> Result<(u32, Value), MigrationError> {
+    match value {
+        // before the envelope, the lists were all there was
+        Value::Array(_) => Ok((1, value)),
+        Value::Object(mut fields) => {
+            let version = fields
+                .get("version")
+                .and_then(Value::as_u64)
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or(MigrationError::Unrecognized)?;
+            let lists = fields.remove("lists").ok_or(MigrationError::Unrecognized)?;
+
+            Ok((version, lists))
+        }
+        _ => Err(MigrationError::Unrecognized),
+    }
+}
+
+/// What the uids made up for older files are derived from
+const MIGRATED_UID_NAMESPACE: Uuid = Uuid::from_u128(0x5c6f_2b0e_8d7a_4f3b_9e21_0a4d_6c8b_1f37);
+
+/// A uid that is the same every time the file is read, so that loading it does not have to save
+/// it again for the uids to last
+fn migrated_uid(key: &str) -> Value {
+    Value::String(Uuid::new_v5(&MIGRATED_UID_NAMESPACE, key.as_bytes()).to_string())
+}
+
+/// The name of the list or item, as part of the key of its uid
+fn name_of(value: &Value) -> &str {
+    value
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+/// Gives every item and subtask a uid, going by where it is and its name
+fn add_uids(mut lists: Value) -> Value {
+    fn add_uid(item: &mut Value, key: String) {
+        let Some(fields) = item.as_object_mut() else {
+            return;
+        };
+
+        if let Some(Value::Array(subtasks)) = fields.get_mut("subtasks") {
+            for (index, subtask) in subtasks.iter_mut().enumerate() {
+                let subtask_key = format!("{key}/{index}/{}", name_of(subtask));
+                add_uid(subtask, subtask_key);
+            }
+        }
+
+        fields.entry("uid").or_insert_with(|| migrated_uid(&key));
+    }
+
+    if let Value::Array(lists) = &mut lists {
+        for (list_index, list) in lists.iter_mut().enumerate() {
+            let list_key = format!("{list_index}/{}", name_of(list));
+
+            if let Some(Value::Array(items)) = list.get_mut("todo_items") {
+                for (index, item) in items.iter_mut().enumerate() {
+                    let key = format!("{list_key}/{index}/{}", name_of(item));
+                    add_uid(item, key);
+                }
+            }
+        }
+    }
+
+    lists
+}
+
+/// Gives every list a uid, going by where it is and its name
+fn add_list_uids(mut lists: Value) -> Value {
+    if let Value::Array(lists) = &mut lists {
+        for (index, list) in lists.iter_mut().enumerate() {
+            let key = format!("{index}/{}", name_of(list));
+
+            if let Some(fields) = list.as_object_mut() {
+                fields.entry("uid").or_insert_with(|| migrated_uid(&key));
+            }
+        }
+    }
+
+    lists
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::TooNew(version) => write!(
+                f,
+                "Saved with format version {version}, newer than the supported {CURRENT_VERSION}"
+            ),
+            MigrationError::Unrecognized => f.write_str("Not a todo save file"),
+            MigrationError::Invalid(error) => f.write_str(error),
+        }
+    }
+}
+
+impl Error for MigrationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1: &str = include_str!("../tests/fixtures/save_file/v1.json");
+    const V2: &str = include_str!("../tests/fixtures/save_file/v2.json");
+    const V3: &str = include_str!("../tests/fixtures/save_file/v3.json");
+    const V4: &str = include_str!("../tests/fixtures/save_file/v4.json");
+    const V9: &str = include_str!("../tests/fixtures/save_file/v9.json");
+    const NO_VERSION: &str = include_str!("../tests/fixtures/save_file/no_version.json");
+
+    fn value(json: &str) -> Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn load(json: &str) -> Result<SaveFile, MigrationError> {
+        SaveFile::try_from(value(json))
+    }
+
+    /// Applies only the migration from the version to the next one
+    fn migrate_once(json: &str) -> (u32, Value) {
+        let (version, lists) = split_version(value(json)).unwrap();
+
+        (version + 1, MIGRATIONS[version as usize - 1](lists))
+    }
+
+    fn item_uids(list: &Value) -> Vec<&Value> {
+        list["todo_items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|item| {
+                let subtasks = item["subtasks"].as_array().into_iter().flatten();
+
+                std::iter::once(&item["uid"]).chain(subtasks.map(|subtask| &subtask["uid"]))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_version_has_a_migration() {
+        assert_eq!(MIGRATIONS.len(), CURRENT_VERSION as usize - 1);
+    }
+
+    #[test]
+    fn migrates_v1_to_v2() {
+        let (version, lists) = migrate_once(V1);
+
+        assert_eq!(version, 2);
+        assert_eq!(lists, split_version(value(V2)).unwrap().1);
+    }
+
+    #[test]
+    fn migrates_v2_to_v3() {
+        let (version, lists) = migrate_once(V2);
+        let uids = item_uids(&lists[0]);
+
+        assert_eq!(version, 3);
+        assert_eq!(uids.len(), 4);
+        assert!(uids.iter().all(|uid| uid
+            .as_str()
+            .and_then(|uid| uid.parse::<Uuid>().ok())
+            .is_some()));
+        assert!(lists[0].get("uid").is_none());
+    }
+
+    #[test]
+    fn migrated_uids_are_the_same_every_time() {
+        assert_eq!(migrate_once(V2), migrate_once(V2));
+        assert_eq!(migrate_once(V3), migrate_once(V3));
+        assert_eq!(
+            load(V1).unwrap().lists[0].todo_items,
+            load(V1).unwrap().lists[0].todo_items
+        );
+    }
+
+    #[test]
+    fn migrates_v3_to_v4() {
+        let (version, lists) = migrate_once(V3);
+        let v3_lists = split_version(value(V3)).unwrap().1;
+
+        assert_eq!(version, 4);
+        assert_eq!(item_uids(&lists[0]), item_uids(&v3_lists[0]));
+        assert!(lists[0]["uid"].is_string());
+        assert_ne!(lists[0]["uid"], lists[1]["uid"]);
+    }
+
+    #[test]
+    fn loads_every_version() {
+        for (json, version) in [(V1, 1), (V2, 2), (V3, 3), (V4, 4)] {
+            let save_file = load(json).unwrap();
+            let names: Vec<_> = save_file.lists.iter().map(|list| &list.name).collect();
+            let bread = &save_file.lists[0].todo_items[1];
+
+            assert_eq!(save_file.version, version);
+            assert_eq!(names, ["Groceries", "Empty"]);
+            assert!(save_file.lists[0].todo_items[0].completed);
+            assert_eq!(bread.due_text().as_deref(), Some("2024-05-01 09:30"));
+            assert_eq!(bread.progress(), Some((1, 2)));
+        }
+    }
+
+    #[test]
+    fn keeps_uids_that_were_saved() {
+        let v3 = load(V3).unwrap();
+        let v4 = load(V4).unwrap();
+        let uids = |save_file: &SaveFile| -> Vec<Uuid> {
+            save_file.lists[0]
+                .todo_items
+                .iter()
+                .flat_map(|item| std::iter::once(item).chain(&item.subtasks))
+                .map(|item| item.uid)
+                .collect()
+        };
+
+        assert_eq!(uids(&v3), uids(&v4));
+        assert_eq!(
+            v4.lists[0].uid,
+            Uuid::parse_str("6f1c2d3e-0000-4000-8000-0000000000a1").unwrap()
+        );
+        assert!(v4.lists[0].todo_items[0].completed_at.is_some());
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        assert!(matches!(load(V9), Err(MigrationError::TooNew(9))));
+    }
+
+    #[test]
+    fn rejects_unrecognized_shapes() {
+        for json in [NO_VERSION, r#""lists""#, "42", r#"{"version": 2}"#] {
+            assert!(matches!(load(json), Err(MigrationError::Unrecognized)));
+        }
+
+        assert!(matches!(
+            load(r#"{"version": 4, "lists": [{"name": 3}]}"#),
+            Err(MigrationError::Invalid(_))
+        ));
+    }
+}
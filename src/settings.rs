@@ -0,0 +1,126 @@
+use std::{fmt, fs, path::PathBuf, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::persistance::{self, PersistError, SaveError};
+
+/// Preferences kept between runs, apart from the todo lists themselves
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Settings {
+    /// The save file to use instead of the default one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<Workspace>,
+}
+
+/// A named save file, to switch between sets of lists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub name: String,
+    pub file: PathBuf,
+}
+
+impl Settings {
+    /// Missing or unreadable settings are just the defaults
+    pub fn load() -> Self {
+        settings_path()
+            .ok()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// The workspaces to pick from, which start out as just the default save file
+    pub fn workspaces(&self) -> Vec<Workspace> {
+        if !self.workspaces.is_empty() {
+            return self.workspaces.clone();
+        }
+
+        persistance::default_path()
+            .map(|file| {
+                vec![Workspace {
+                    name: "Default".to_owned(),
+                    file,
+                }]
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn workspace(&self, name: &str) -> Option<Workspace> {
+        self.workspaces()
+            .into_iter()
+            .find(|workspace| workspace.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Adds a workspace with its own save file next to the default one, unless the name is taken
+    pub fn add_workspace(&mut self, name: &str) -> Result<Option<Workspace>, PersistError> {
+        if self.workspace(name).is_some() {
+            return Ok(None);
+        }
+
+        let slug: String = name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let default_path = persistance::default_path()?;
+
+        // names differing only in punctuation would otherwise share a file
+        let file = (1..)
+            .map(|number| {
+                let suffix = if number > 1 {
+                    format!("_{number}")
+                } else {
+                    String::new()
+                };
+
+                default_path.with_file_name(format!("todo_save_{slug}{suffix}.json"))
+            })
+            .find(|file| {
+                !file.exists()
+                    && self
+                        .workspaces()
+                        .iter()
+                        .all(|workspace| workspace.file != *file)
+            })
+            .unwrap_or_default();
+
+        let workspace = Workspace {
+            name: name.to_owned(),
+            file,
+        };
+
+        self.workspaces = self.workspaces();
+        self.workspaces.push(workspace.clone());
+
+        Ok(Some(workspace))
+    }
+
+    pub fn save(&self) -> Result<(), PersistError> {
+        let path = settings_path()?;
+        let settings_string = serde_json::to_string(self)
+            .map_err(|error| PersistError::Save(SaveError::Compose(Arc::new(error))))?;
+
+        fs::write(&path, settings_string)
+            .map_err(|error| PersistError::Save(SaveError::Write(path, Arc::new(error))))
+    }
+}
+
+fn settings_path() -> Result<PathBuf, PersistError> {
+    let mut path_buf = dirs::config_dir().ok_or(PersistError::Path)?;
+    path_buf.push("todo_settings.json");
+
+    Ok(path_buf)
+}
+
+impl fmt::Display for Workspace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
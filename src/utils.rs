@@ -1,3 +1,5 @@
+use chrono::{DateTime, Utc};
+
 pub fn strip_trailing_newline(s: &str) -> String {
     s.strip_suffix("\r\n")
         .or(s.strip_suffix("\n"))
@@ -20,3 +22,29 @@ where
 {
     *current_val || items.iter().any(check_fn)
 }
+
+/// How long ago the time was, like "2 days ago"
+pub fn relative_time(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(then);
+    let days = elapsed.num_days();
+
+    let (count, unit) = if elapsed.num_minutes() < 1 {
+        return "just now".to_owned();
+    } else if elapsed.num_hours() < 1 {
+        (elapsed.num_minutes(), "minute")
+    } else if days < 1 {
+        (elapsed.num_hours(), "hour")
+    } else if days == 1 {
+        return "yesterday".to_owned();
+    } else if days < 7 {
+        (days, "day")
+    } else if days < 30 {
+        (days / 7, "week")
+    } else if days < 365 {
+        (days / 30, "month")
+    } else {
+        (days / 365, "year")
+    };
+
+    format!("{count} {unit}{} ago", if count == 1 { "" } else { "s" })
+}